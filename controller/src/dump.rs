@@ -0,0 +1,176 @@
+use utils_state::StateDumpLoader;
+
+use crate::view::ViewController;
+
+/// Register all states which are included within state dumps.
+/// This includes the CS2 states (see [cs2::register_dump_states]).
+/// Used to load a dump attached to a bug report as a fixture.
+#[allow(unused)]
+pub fn register_dump_states(loader: &mut StateDumpLoader) -> &mut StateDumpLoader {
+    cs2::register_dump_states(loader).register::<ViewController>()
+}
+
+#[cfg(test)]
+mod test {
+    use cs2::{
+        BombDefuser,
+        Bone,
+        CS2Model,
+        PlantedC4,
+        PlantedC4State,
+        SpectatorInfo,
+        SpectatorList,
+        StateBuildInfo,
+        StateCurrentMap,
+        StatePawnInfo,
+        WeaponId,
+    };
+    use cs2_schema_cutl::EntityHandle;
+    use utils_state::{
+        State,
+        StateDump,
+        StateDumpLoader,
+        StateRegistry,
+    };
+
+    use super::register_dump_states;
+    use crate::view::ViewController;
+
+    #[test]
+    fn test_dump_roundtrip() {
+        let mut states = StateRegistry::new(16);
+        states
+            .set(
+                StatePawnInfo {
+                    controller_entity_id: Some(1),
+                    pawn_entity_id: 2,
+                    team_id: 3,
+
+                    player_health: 100,
+                    player_has_defuser: true,
+                    player_name: Some("player".to_string()),
+                    weapon: WeaponId::Deagle,
+                    player_flashtime: 0.0,
+
+                    position: nalgebra::Vector3::new(1.0, 2.0, 3.0),
+                    rotation: 90.0,
+                },
+                EntityHandle::from_index(2),
+            )
+            .unwrap();
+        states
+            .set(
+                StateBuildInfo {
+                    revision: "revision".to_string(),
+                    build_datetime: "datetime".to_string(),
+                },
+                (),
+            )
+            .unwrap();
+        states
+            .set(
+                StateCurrentMap {
+                    current_map: Some("de_dust2".to_string()),
+                },
+                (),
+            )
+            .unwrap();
+        states
+            .set(
+                SpectatorList {
+                    target_entity_id: 2,
+                    spectators: vec![SpectatorInfo {
+                        spectator_name: "spectator".to_string(),
+                    }],
+                },
+                2,
+            )
+            .unwrap();
+        states
+            .set(
+                PlantedC4 {
+                    bomb_site: 1,
+                    state: PlantedC4State::Active {
+                        time_detonation: 10.0,
+                    },
+                    defuser: Some(BombDefuser {
+                        time_remaining: 5.0,
+                        player_name: "defuser".to_string(),
+                    }),
+                },
+                (),
+            )
+            .unwrap();
+        states
+            .set(
+                CS2Model {
+                    name: "model".to_string(),
+                    bones: vec![Bone {
+                        name: "head".to_string(),
+                        flags: 0,
+                        parent: None,
+                    }],
+                    ..Default::default()
+                },
+                0x1000,
+            )
+            .unwrap();
+
+        let mut view_controller = ViewController::create(&states, ()).unwrap();
+        view_controller.update_screen_bounds(mint::Vector2 {
+            x: 1920.0,
+            y: 1080.0,
+        });
+        states.set(view_controller, ()).unwrap();
+
+        let dump = states.dump_states().unwrap();
+        assert_eq!(dump.states.len(), 7);
+
+        let dump = StateDump::from_json(&dump.to_json().unwrap()).unwrap();
+        let mut loaded_states = StateRegistry::new(16);
+        let result = register_dump_states(&mut StateDumpLoader::new())
+            .load(&mut loaded_states, &dump)
+            .unwrap();
+        assert_eq!(result.loaded, 7);
+        assert!(result.skipped_types.is_empty());
+
+        let pawn_info = loaded_states
+            .get::<StatePawnInfo>(EntityHandle::from_index(2))
+            .unwrap();
+        assert_eq!(pawn_info.player_name.as_deref(), Some("player"));
+        assert_eq!(pawn_info.weapon, WeaponId::Deagle);
+
+        assert_eq!(
+            loaded_states.get::<StateBuildInfo>(()).unwrap().revision,
+            "revision"
+        );
+        assert_eq!(
+            loaded_states
+                .get::<StateCurrentMap>(())
+                .unwrap()
+                .current_map
+                .as_deref(),
+            Some("de_dust2")
+        );
+        assert_eq!(
+            loaded_states.get::<SpectatorList>(2).unwrap().spectators[0].spectator_name,
+            "spectator"
+        );
+        assert!(matches!(
+            loaded_states.get::<PlantedC4>(()).unwrap().state,
+            PlantedC4State::Active { .. }
+        ));
+        assert_eq!(
+            loaded_states.get::<CS2Model>(0x1000).unwrap().bones[0].name,
+            "head"
+        );
+        assert_eq!(
+            loaded_states
+                .get::<ViewController>(())
+                .unwrap()
+                .screen_bounds
+                .x,
+            1920.0
+        );
+    }
+}
@@ -18,6 +18,7 @@ use std::{
     time::{
         Duration,
         Instant,
+        SystemTime,
     },
 };
 
@@ -25,11 +26,11 @@ use anyhow::Context;
 use clap::Parser;
 use cs2::{
     CS2Handle,
-    ConVars,
     InterfaceError,
     StateBuildInfo,
     StateCS2Handle,
     StateCS2Memory,
+    StateConVar,
 };
 use enhancements::{
     Enhancement,
@@ -59,7 +60,10 @@ use settings::{
 };
 use tokio::runtime;
 use utils::show_critical_error;
-use utils_state::StateRegistry;
+use utils_state::{
+    StateLimits,
+    StateRegistry,
+};
 use view::ViewController;
 use windows::Win32::UI::Shell::IsUserAnAdmin;
 
@@ -78,6 +82,7 @@ use crate::{
 };
 
 mod dialog;
+mod dump;
 mod enhancements;
 mod settings;
 mod utils;
@@ -236,6 +241,9 @@ impl Application {
             }
         }
 
+        let render_debug_window = self.settings().render_debug_window;
+        self.app_state.set_tracing(render_debug_window);
+
         self.app_state.invalidate_states();
         if let Ok(mut view_controller) = self.app_state.resolve_mut::<ViewController>(()) {
             view_controller.update_screen_bounds(mint::Vector2::from_slice(&ui.io().display_size));
@@ -279,12 +287,80 @@ impl Application {
             }
         }
 
+        if self.settings().render_debug_window {
+            self.render_state_trace(ui);
+        }
+
         if self.settings_visible {
             let mut settings_ui = self.settings_ui.borrow_mut();
             settings_ui.render(self, ui, unicode_text)
         }
     }
 
+    /// Render the most expensive states resolved within the last frame
+    fn render_state_trace(&self, ui: &imgui::Ui) {
+        let Some(trace) = self.app_state.last_trace() else {
+            return;
+        };
+        let statistics = self.app_state.statistics();
+
+        ui.window(obfstr!("State Registry"))
+            .size([600.0, 400.0], Condition::FirstUseEver)
+            .build(|| {
+                ui.text(format!(
+                    "{} states resolved in {:.2?}",
+                    trace.nodes.len(),
+                    trace.duration
+                ));
+                ui.text(format!(
+                    "{} / {} slots in use, {} evictions",
+                    statistics.live_states,
+                    statistics.capacity,
+                    statistics
+                        .types
+                        .values()
+                        .map(|occupancy| occupancy.evictions)
+                        .sum::<usize>()
+                ));
+                if ui.button(obfstr!("Save state dump")) {
+                    if let Err(err) = self.save_state_dump() {
+                        log::error!("{}: {:#}", obfstr!("Failed to save state dump"), err);
+                    }
+                }
+                ui.separator();
+
+                for node in trace.nodes_by_time().into_iter().take(32) {
+                    ui.text(format!(
+                        "{:>10.2?} {} (hits: {}, misses: {}, updates: {}, errors: {})",
+                        node.total_time(),
+                        node.state_type,
+                        node.cache_hits,
+                        node.cache_misses,
+                        node.updates,
+                        node.errors
+                    ));
+                }
+            });
+    }
+
+    /// Save all serializable states to a file which can be attached to bug reports
+    fn save_state_dump(&self) -> anyhow::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
+        let file_name = format!("state_dump_{}.json", timestamp);
+
+        let dump = self.app_state.dump_states()?;
+        std::fs::write(&file_name, dump.to_json()?)?;
+        log::info!(
+            "{} {} ({} states)",
+            obfstr!("Saved state dump to"),
+            file_name,
+            dump.states.len()
+        );
+        Ok(())
+    }
+
     fn render_overlay(&self, ui: &imgui::Ui, unicode_text: &UnicodeTextRenderer) {
         let settings = self.settings();
 
@@ -363,10 +439,14 @@ struct AppArgs {
     #[clap(short, long)]
     verbose: bool,
 
-    /// Load the CS2 schema (offsets) from a file
-    /// instead of resolving them at runtime by the CS2 schema system.
+    /// Load the CS2 schema (offsets) from a file.
+    /// Offsets resolved at runtime by the CS2 schema system take precedence.
     #[arg(short, long)]
     schema_file: Option<PathBuf>,
+
+    /// Do not cache the resolved offsets for the current CS2 build
+    #[arg(long)]
+    no_offset_cache: bool,
 }
 
 fn real_main(args: &AppArgs) -> anyhow::Result<()> {
@@ -430,7 +510,7 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
 
     cs2.add_metrics_record(obfstr!("controller-status"), "initializing");
 
-    let mut app_state = StateRegistry::new(1024 * 8);
+    let mut app_state = StateRegistry::with_limits(StateLimits::default());
     app_state.set(StateCS2Handle::new(cs2.clone()), ())?;
     app_state.set(StateCS2Memory::new(cs2.create_memory_view()), ())?;
     app_state.set(settings, ())?;
@@ -459,30 +539,39 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
         if let Some(file) = &args.schema_file {
             log::info!(
                 "{} {}",
-                obfstr!("Loading CS2 schema (offsets) from file"),
+                obfstr!("Loading CS2 schema (offsets) from CS2 schema system and file"),
                 file.display()
             );
-
-            cs2_schema_provider_impl::setup_schema_from_file(&mut app_state, file)
-                .context("file schema setup")?;
         } else {
             log::info!(
                 "{}",
                 obfstr!("Loading CS2 schema (offsets) from CS2 schema system")
             );
-            cs2_schema_provider_impl::setup_provider(Box::new(
-                cs2_schema_provider_impl::RuntimeSchemaProvider::new(&app_state)
-                    .context("load runtime schema")?,
-            ));
         }
+
+        let offset_cache = if args.no_offset_cache {
+            None
+        } else {
+            Some(cs2_schema_provider_impl::OffsetCache::new(
+                cs2_schema_provider_impl::OffsetCache::default_directory()?,
+            ))
+        };
+
+        let schema_provider = cs2_schema_provider_impl::setup_layered_schema(
+            &mut app_state,
+            args.schema_file.as_deref(),
+            offset_cache.as_ref(),
+        )
+        .context("schema setup")?;
         log::info!("CS2 schema (offsets) loaded.");
+
+        cs2_schema_provider_impl::validate_runtime_offsets();
+        schema_provider.diagnostics().log_summary();
     }
 
-    let cvars = ConVars::new(&app_state).context("cvars")?;
-    let cvar_sensitivity = cvars
-        .find_cvar("sensitivity")
-        .context("cvar ensitivity")?
-        .context("missing cvar sensitivity")?;
+    app_state
+        .resolve::<StateConVar>("sensitivity")
+        .context("cvar sensitivity")?;
 
     log::debug!("Initialize overlay");
     let app_fonts: AppFonts = Default::default();
@@ -547,7 +636,7 @@ fn real_main(args: &AppArgs) -> anyhow::Result<()> {
         cs2: cs2.clone(),
 
         enhancements: vec![
-            Rc::new(RefCell::new(AntiAimPunsh::new(cvar_sensitivity))),
+            Rc::new(RefCell::new(AntiAimPunsh::new())),
             Rc::new(RefCell::new(PlayerESP::new())),
             Rc::new(RefCell::new(SpectatorsListIndicator::new())),
             Rc::new(RefCell::new(BombInfoIndicator::new())),
@@ -4,14 +4,20 @@ use cs2::{
     StateResolvedOffset,
 };
 use imgui::ImColor32;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
     StateRegistry,
+    StateSerializer,
 };
 
 /// View controller which helps resolve in game
 /// coordinates into 2d screen coordinates.
+#[derive(Serialize, Deserialize)]
 pub struct ViewController {
     view_matrix: nalgebra::Matrix4<f32>,
     pub screen_bounds: mint::Vector2<f32>,
@@ -31,6 +37,10 @@ impl State for ViewController {
         StateCacheType::Persistent
     }
 
+    fn serializer() -> Option<StateSerializer> {
+        Some(StateSerializer::of::<Self>("view-controller"))
+    }
+
     fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
         let cs2 = states.resolve::<StateCS2Handle>(())?;
         let offset = states.resolve::<StateResolvedOffset>(CS2Offset::ViewMatrix)?;
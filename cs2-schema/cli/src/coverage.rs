@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use cs2_schema_definition::{
+    DumpedSchema,
+    SchemaCoverage,
+};
+
+#[derive(Debug, Args)]
+pub struct CoverageArgs {
+    /// Schema file to report the coverage for
+    schema: PathBuf,
+
+    /// Output the coverage as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+pub fn execute(args: CoverageArgs) -> anyhow::Result<()> {
+    let schema = DumpedSchema::from_file(&args.schema)?;
+    let coverage = SchemaCoverage::compute(&schema);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&coverage)?);
+    } else {
+        print!("{}", coverage);
+    }
+
+    Ok(())
+}
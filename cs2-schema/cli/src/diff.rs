@@ -0,0 +1,90 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use anyhow::Context;
+use clap::Args;
+use cs2_schema_definition::{
+    DumpedSchema,
+    FieldReference,
+    SchemaDiff,
+};
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Schema file of the old CS2 build
+    old_schema: PathBuf,
+
+    /// Schema file of the new CS2 build
+    new_schema: PathBuf,
+
+    /// Output the diff as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Only report changes of fields used by the generated bindings (cs2-schema-generated).
+    /// The value can either be the schema file the bindings have been generated from
+    /// or a directory containing the generated Rust sources.
+    #[arg(long, value_name = "SCHEMA_OR_DIR")]
+    used_fields: Option<PathBuf>,
+}
+
+fn collect_sources_fields(
+    path: &Path,
+    result: &mut BTreeSet<FieldReference>,
+) -> anyhow::Result<()> {
+    for entry in fs::read_dir(path).with_context(|| format!("read {}", path.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_sources_fields(&path, result)?;
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            let source =
+                fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+            result.extend(cs2_schema_definition::collect_runtime_offset_calls(&source));
+        }
+    }
+
+    Ok(())
+}
+
+fn load_used_fields(path: &Path) -> anyhow::Result<BTreeSet<FieldReference>> {
+    if path.is_dir() {
+        let mut result = Default::default();
+        collect_sources_fields(path, &mut result)?;
+        Ok(result)
+    } else {
+        let schema = DumpedSchema::from_file(path)?;
+        Ok(cs2_schema_definition::collect_bound_fields(&schema))
+    }
+}
+
+pub fn execute(args: DiffArgs) -> anyhow::Result<()> {
+    let old_schema = DumpedSchema::from_file(&args.old_schema)?;
+    let new_schema = DumpedSchema::from_file(&args.new_schema)?;
+
+    let diff = if let Some(used_fields) = &args.used_fields {
+        let used_fields = load_used_fields(used_fields).context("used fields")?;
+        if used_fields.is_empty() {
+            anyhow::bail!("could not find any used fields");
+        }
+
+        SchemaDiff::compute_filtered(&old_schema, &new_schema, &|field| {
+            used_fields.contains(field)
+        })
+    } else {
+        SchemaDiff::compute(&old_schema, &new_schema)
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{}", diff);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use clap::{
+    Parser,
+    Subcommand,
+};
+
+mod coverage;
+mod diff;
+mod query;
+
+/// CS2 schema tooling
+#[derive(Debug, Parser)]
+#[clap(version)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Compare two schema files
+    Diff(diff::DiffArgs),
+
+    /// Report how many schema fields have a generated Rust type
+    Coverage(coverage::CoverageArgs),
+
+    /// Search classes, fields and enums of a schema file
+    Query(query::QueryArgs),
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    match args.command {
+        Command::Diff(args) => diff::execute(args),
+        Command::Coverage(args) => coverage::execute(args),
+        Command::Query(args) => query::execute(args),
+    }
+}
@@ -0,0 +1,701 @@
+use std::{
+    collections::BTreeSet,
+    path::PathBuf,
+};
+
+use clap::{
+    Args,
+    Subcommand,
+};
+use cs2_schema_definition::{
+    mod_name_from_schema_name,
+    ClassDefinition,
+    ClassField,
+    ClassReference,
+    DumpedSchema,
+    EnumDefinition,
+    InheritageMap,
+    Metadata,
+};
+
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    /// Schema file to query
+    schema: PathBuf,
+
+    #[command(subcommand)]
+    command: QueryCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum QueryCommand {
+    /// Fuzzy search class, field and enum names
+    Search {
+        pattern: String,
+
+        /// Maximum number of results
+        #[arg(long, default_value_t = 25)]
+        limit: usize,
+    },
+
+    /// Print a class including all inherited fields
+    Class {
+        /// Class name (optionally prefixed with the module, e.g. client::C_BaseEntity)
+        name: String,
+    },
+
+    /// Print all classes inheriting from a class
+    Subclasses {
+        /// Class name (optionally prefixed with the module, e.g. client::C_BaseEntity)
+        name: String,
+    },
+
+    /// List network enabled fields
+    Network {
+        /// Only list the network fields of this class (including inherited fields)
+        class: Option<String>,
+    },
+
+    /// Print the members of an enum
+    Enum {
+        /// Enum name (optionally prefixed with the module, e.g. client::CSWeaponType)
+        name: String,
+    },
+}
+
+/// Case insensitive fuzzy match score of the pattern within the candidate.
+/// Substring matches score higher than characters matching in order.
+fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i64> {
+    let pattern = pattern.to_lowercase();
+    let candidate = candidate.to_lowercase();
+
+    if candidate == pattern {
+        return Some(1_000_000);
+    }
+
+    if let Some(position) = candidate.find(&pattern) {
+        return Some(100_000 - position as i64 - candidate.len() as i64);
+    }
+
+    let mut score = 10_000;
+    let mut candidate_chars = candidate.chars().enumerate();
+    let mut last_match = None;
+    for pattern_char in pattern.chars() {
+        let (index, _) = candidate_chars.find(|(_, char)| *char == pattern_char)?;
+        if let Some(last_match) = last_match {
+            score -= (index - last_match - 1) as i64;
+        }
+        last_match = Some(index);
+    }
+
+    Some(score - candidate.len() as i64)
+}
+
+struct SchemaIndex<'a> {
+    schema: &'a DumpedSchema,
+    inheritage: InheritageMap,
+}
+
+impl<'a> SchemaIndex<'a> {
+    fn new(schema: &'a DumpedSchema) -> Self {
+        Self {
+            schema,
+            inheritage: InheritageMap::build(&schema.scopes),
+        }
+    }
+
+    /// All classes referenced by their Rust name (see [InheritageMap])
+    fn classes(&self) -> impl Iterator<Item = (ClassReference, &'a ClassDefinition)> {
+        self.schema.scopes.iter().flat_map(|scope| {
+            scope.classes.iter().map(|class| {
+                (
+                    ClassReference {
+                        module_name: mod_name_from_schema_name(&scope.schema_name).to_string(),
+                        class_name: class.class_name.replace(":", "_"),
+                    },
+                    class,
+                )
+            })
+        })
+    }
+
+    fn enums(&self) -> impl Iterator<Item = (&'a str, &'a EnumDefinition)> {
+        self.schema.scopes.iter().flat_map(|scope| {
+            scope
+                .enums
+                .iter()
+                .map(|definition| (mod_name_from_schema_name(&scope.schema_name), definition))
+        })
+    }
+
+    fn class(&self, reference: &ClassReference) -> Option<&'a ClassDefinition> {
+        self.classes()
+            .find(|(class_reference, _)| class_reference == reference)
+            .map(|(_, class)| class)
+    }
+
+    /// Split the name into the module and the item name.
+    /// The name is only treated as qualified if the prefix is a known module as
+    /// nested names (e.g. `CNmGraphNode::CDefinition`) contain `::` as well.
+    fn split_module_name<'n>(&self, name: &'n str) -> (Option<&'n str>, &'n str) {
+        match name.split_once("::") {
+            Some((module_name, item_name))
+                if self
+                    .schema
+                    .scopes
+                    .iter()
+                    .any(|scope| mod_name_from_schema_name(&scope.schema_name) == module_name) =>
+            {
+                (Some(module_name), item_name)
+            }
+            _ => (None, name),
+        }
+    }
+
+    fn find_class(&self, name: &str) -> anyhow::Result<(ClassReference, &'a ClassDefinition)> {
+        let (module_name, class_name) = self.split_module_name(name);
+        let class_name = class_name.replace(":", "_");
+        let class_name = class_name.as_str();
+
+        let matches = self
+            .classes()
+            .filter(|(reference, _)| {
+                reference.class_name == class_name
+                    && module_name.is_none_or(|module_name| reference.module_name == module_name)
+            })
+            .collect::<Vec<_>>();
+
+        match matches.len() {
+            1 => Ok(matches.into_iter().next().unwrap()),
+            0 => {
+                let mut suggestions = self
+                    .classes()
+                    .filter_map(|(reference, _)| {
+                        fuzzy_score(class_name, &reference.class_name)
+                            .map(|score| (score, reference))
+                    })
+                    .collect::<Vec<_>>();
+                suggestions.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+                let suggestions = suggestions
+                    .iter()
+                    .take(5)
+                    .map(|(_, reference)| {
+                        format!("{}::{}", reference.module_name, reference.class_name)
+                    })
+                    .collect::<Vec<_>>();
+
+                if suggestions.is_empty() {
+                    anyhow::bail!("class {} not found", name)
+                } else {
+                    anyhow::bail!(
+                        "class {} not found. Did you mean: {}",
+                        name,
+                        suggestions.join(", ")
+                    )
+                }
+            }
+            _ => anyhow::bail!(
+                "class {} is ambiguous: {}",
+                name,
+                matches
+                    .iter()
+                    .map(|(reference, _)| format!(
+                        "{}::{}",
+                        reference.module_name, reference.class_name
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// All enums with the given name (optionally prefixed with the module)
+    fn find_enums(&self, name: &str) -> anyhow::Result<Vec<(&'a str, &'a EnumDefinition)>> {
+        let (module_name, enum_name) = self.split_module_name(name);
+
+        let matches = self
+            .enums()
+            .filter(|(enum_module, definition)| {
+                definition.enum_name == enum_name
+                    && module_name.is_none_or(|module_name| module_name == *enum_module)
+            })
+            .collect::<Vec<_>>();
+
+        if matches.is_empty() {
+            anyhow::bail!("enum {} not found", name);
+        }
+
+        Ok(matches)
+    }
+
+    /// All fields of the class including the inherited fields, ordered by their offset.
+    fn flattened_fields(
+        &self,
+        reference: &ClassReference,
+    ) -> Vec<(ClassReference, &'a ClassField)> {
+        let mut classes = vec![reference.clone()];
+        classes.extend(self.inheritage.get_inheritance_chain(reference));
+
+        let mut fields = classes
+            .into_iter()
+            .filter_map(|reference| self.class(&reference).map(|class| (reference, class)))
+            .flat_map(|(reference, class)| {
+                class
+                    .offsets
+                    .iter()
+                    .map(move |field| (reference.clone(), field))
+            })
+            .collect::<Vec<_>>();
+
+        fields.sort_by_key(|(_, field)| field.offset);
+        fields
+    }
+}
+
+/// All classes, fields and enums matching the pattern, best match first
+fn search(index: &SchemaIndex, pattern: &str) -> Vec<String> {
+    let mut results = Vec::new();
+
+    for (reference, class) in index.classes() {
+        if let Some(score) = fuzzy_score(pattern, &reference.class_name) {
+            results.push((
+                score,
+                format!(
+                    "class  {}::{} (size 0x{:X})",
+                    reference.module_name, reference.class_name, class.class_size
+                ),
+            ));
+        }
+
+        for field in class.offsets.iter() {
+            if let Some(score) = fuzzy_score(pattern, &field.field_name) {
+                results.push((
+                    score,
+                    format!(
+                        "field  {}::{}.{} = 0x{:X} ({})",
+                        reference.module_name,
+                        reference.class_name,
+                        field.field_name,
+                        field.offset,
+                        field.field_ctype
+                    ),
+                ));
+            }
+        }
+    }
+
+    for (module_name, definition) in index.enums() {
+        if let Some(score) = fuzzy_score(pattern, &definition.enum_name) {
+            results.push((
+                score,
+                format!("enum   {}::{}", module_name, definition.enum_name),
+            ));
+        }
+    }
+
+    results.sort_by(|(score_a, name_a), (score_b, name_b)| {
+        score_b.cmp(score_a).then_with(|| name_a.cmp(name_b))
+    });
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn print_search(index: &SchemaIndex, pattern: &str, limit: usize) {
+    let results = search(index, pattern);
+    for result in results.iter().take(limit) {
+        println!("{}", result);
+    }
+
+    if results.len() > limit {
+        println!("... {} more results", results.len() - limit);
+    }
+}
+
+fn print_class(index: &SchemaIndex, name: &str) -> anyhow::Result<()> {
+    let (reference, class) = index.find_class(name)?;
+
+    let chain = index.inheritage.get_inheritance_chain(&reference);
+    println!(
+        "class {}::{} (size 0x{:X})",
+        reference.module_name, reference.class_name, class.class_size
+    );
+    for base_class in chain.iter() {
+        println!(
+            "  inherits {}::{}",
+            base_class.module_name, base_class.class_name
+        );
+    }
+
+    println!();
+    for (owner, field) in index.flattened_fields(&reference) {
+        println!(
+            "  0x{:04X} {:<40} {:<48} {}",
+            field.offset,
+            field.field_name,
+            field.field_ctype,
+            if owner == reference {
+                String::new()
+            } else {
+                format!("({}::{})", owner.module_name, owner.class_name)
+            }
+        );
+    }
+
+    Ok(())
+}
+
+fn print_subclasses(
+    index: &SchemaIndex,
+    reference: &ClassReference,
+    depth: usize,
+    visited: &mut BTreeSet<ClassReference>,
+) -> anyhow::Result<()> {
+    let mut derived_classes = index.inheritage.get_derived_classes(reference);
+    derived_classes.sort();
+
+    for derived_class in derived_classes {
+        println!(
+            "{}{}::{}",
+            "  ".repeat(depth),
+            derived_class.module_name,
+            derived_class.class_name
+        );
+
+        if !visited.insert(derived_class.clone()) {
+            /* class already visited (cyclic inheritance) */
+            continue;
+        }
+
+        print_subclasses(index, &derived_class, depth + 1, visited)?;
+    }
+
+    Ok(())
+}
+
+fn is_network_field(field: &ClassField) -> bool {
+    field
+        .metadata
+        .iter()
+        .any(|metadata| matches!(metadata, Metadata::NetworkEnable))
+}
+
+fn print_network_var_names(class: &ClassDefinition) {
+    for metadata in class.metadata.iter() {
+        if let Metadata::NetworkVarNames { var_name, var_type } = metadata {
+            println!("    var {} ({})", var_name, var_type);
+        }
+    }
+}
+
+fn print_network(index: &SchemaIndex, class: Option<&str>) -> anyhow::Result<()> {
+    if let Some(class) = class {
+        let (reference, class) = index.find_class(class)?;
+        println!("{}::{}", reference.module_name, reference.class_name);
+        print_network_var_names(class);
+
+        for (owner, field) in index.flattened_fields(&reference) {
+            if !is_network_field(field) {
+                continue;
+            }
+
+            println!(
+                "  0x{:04X} {:<40} {:<48} {}",
+                field.offset,
+                field.field_name,
+                field.field_ctype,
+                if owner == reference {
+                    String::new()
+                } else {
+                    format!("({}::{})", owner.module_name, owner.class_name)
+                }
+            );
+        }
+
+        return Ok(());
+    }
+
+    for (reference, class) in index.classes() {
+        let fields = class
+            .offsets
+            .iter()
+            .filter(|field| is_network_field(field))
+            .collect::<Vec<_>>();
+        if fields.is_empty() {
+            continue;
+        }
+
+        println!("{}::{}", reference.module_name, reference.class_name);
+        print_network_var_names(class);
+        for field in fields {
+            println!(
+                "  0x{:04X} {:<40} {}",
+                field.offset, field.field_name, field.field_ctype
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn print_enum(index: &SchemaIndex, name: &str) -> anyhow::Result<()> {
+    for (enum_module, definition) in index.find_enums(name)? {
+        println!(
+            "enum {}::{} ({} bytes)",
+            enum_module, definition.enum_name, definition.enum_size
+        );
+        for member in definition.memebers.iter() {
+            println!("  {:<48} 0x{:X}", member.name, member.value);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn execute(args: QueryArgs) -> anyhow::Result<()> {
+    let schema = DumpedSchema::from_file(&args.schema)?;
+    let index = SchemaIndex::new(&schema);
+
+    match args.command {
+        QueryCommand::Search { pattern, limit } => {
+            print_search(&index, &pattern, limit);
+            Ok(())
+        }
+        QueryCommand::Class { name } => print_class(&index, &name),
+        QueryCommand::Subclasses { name } => {
+            let (reference, _) = index.find_class(&name)?;
+            println!("{}::{}", reference.module_name, reference.class_name);
+            print_subclasses(
+                &index,
+                &reference,
+                1,
+                &mut BTreeSet::from([reference.clone()]),
+            )
+        }
+        QueryCommand::Network { class } => print_network(&index, class.as_deref()),
+        QueryCommand::Enum { name } => print_enum(&index, &name),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use cs2_schema_definition::DumpedSchema;
+
+    use super::{
+        print_subclasses,
+        search,
+        SchemaIndex,
+    };
+
+    const SCHEMA: &str = r#"{
+        "format_version": 1,
+        "scopes": [
+            {
+                "schema_name": "client.dll",
+                "classes": [
+                    {
+                        "class_name": "C_BaseEntity",
+                        "class_size": 64,
+                        "offsets": [
+                            { "field_name": "m_iHealth", "field_type": "i32", "field_ctype": "int32", "offset": 16, "metadata": [] },
+                            { "field_name": "m_iTeamNum", "field_type": "u8", "field_ctype": "uint8", "offset": 20, "metadata": [] }
+                        ]
+                    },
+                    {
+                        "class_name": "C_BasePlayerPawn",
+                        "class_size": 128,
+                        "inherits": "client::C_BaseEntity",
+                        "offsets": [
+                            { "field_name": "m_iMaxHealth", "field_type": "i32", "field_ctype": "int32", "offset": 8, "metadata": [] },
+                            { "field_name": "m_ArmorValue", "field_type": "i32", "field_ctype": "int32", "offset": 64, "metadata": [] }
+                        ]
+                    },
+                    {
+                        "class_name": "CNmGraphNode::CDefinition",
+                        "class_size": 16,
+                        "offsets": [
+                            { "field_name": "m_nNodeIdx", "field_type": "i16", "field_ctype": "int16", "offset": 8, "metadata": [] }
+                        ]
+                    },
+                    {
+                        "class_name": "CNmPoseNode::CDefinition",
+                        "class_size": 24,
+                        "inherits": "client::CNmGraphNode__CDefinition",
+                        "offsets": [
+                            { "field_name": "m_flPlayTime", "field_type": "f32", "field_ctype": "float32", "offset": 16, "metadata": [] }
+                        ]
+                    },
+                    {
+                        "class_name": "CNmBlendNode::CDefinition",
+                        "class_size": 32,
+                        "inherits": "client::CNmPoseNode__CDefinition",
+                        "offsets": []
+                    },
+                    { "class_name": "CCycleA", "class_size": 8, "inherits": "client::CCycleB", "offsets": [] },
+                    { "class_name": "CCycleB", "class_size": 8, "inherits": "client::CCycleA", "offsets": [] }
+                ],
+                "enums": [
+                    {
+                        "enum_name": "MoveType_t",
+                        "enum_size": 1,
+                        "memebers": [
+                            { "name": "MOVETYPE_NONE", "value": 0 },
+                            { "name": "MOVETYPE_WALK", "value": 2 }
+                        ]
+                    }
+                ]
+            },
+            {
+                "schema_name": "server.dll",
+                "classes": [
+                    { "class_name": "C_BaseEntity", "class_size": 32, "offsets": [] }
+                ]
+            }
+        ]
+    }"#;
+
+    fn load_schema() -> DumpedSchema {
+        DumpedSchema::from_reader(SCHEMA.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_find_class() {
+        let schema = load_schema();
+        let index = SchemaIndex::new(&schema);
+
+        let (reference, class) = index.find_class("C_BasePlayerPawn").unwrap();
+        assert_eq!(reference.module_name, "client");
+        assert_eq!(class.class_size, 128);
+
+        let (reference, class) = index.find_class("server::C_BaseEntity").unwrap();
+        assert_eq!(reference.module_name, "server");
+        assert_eq!(class.class_size, 32);
+
+        let error = index.find_class("C_BaseEntity").err().unwrap();
+        assert!(error.to_string().contains("ambiguous"));
+
+        let error = index.find_class("C_BasePlayerPwn").err().unwrap();
+        assert!(error
+            .to_string()
+            .contains("Did you mean: client::C_BasePlayerPawn"));
+    }
+
+    #[test]
+    fn test_flattened_fields() {
+        let schema = load_schema();
+        let index = SchemaIndex::new(&schema);
+
+        let (reference, _) = index.find_class("C_BasePlayerPawn").unwrap();
+        let fields = index
+            .flattened_fields(&reference)
+            .into_iter()
+            .map(|(owner, field)| (owner.class_name, field.field_name.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fields,
+            vec![
+                ("C_BasePlayerPawn".to_string(), "m_iMaxHealth"),
+                ("C_BaseEntity".to_string(), "m_iHealth"),
+                ("C_BaseEntity".to_string(), "m_iTeamNum"),
+                ("C_BasePlayerPawn".to_string(), "m_ArmorValue"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_class() {
+        let schema = load_schema();
+        let index = SchemaIndex::new(&schema);
+
+        /* the nested class name must not be treated as module name */
+        let (reference, class) = index.find_class("CNmPoseNode::CDefinition").unwrap();
+        assert_eq!(reference.module_name, "client");
+        assert_eq!(reference.class_name, "CNmPoseNode__CDefinition");
+        assert_eq!(class.class_size, 24);
+
+        let (reference, _) = index
+            .find_class("client::CNmBlendNode::CDefinition")
+            .unwrap();
+        let chain = index
+            .inheritage
+            .get_inheritance_chain(&reference)
+            .into_iter()
+            .map(|reference| format!("{}::{}", reference.module_name, reference.class_name))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            chain,
+            vec![
+                "client::CNmPoseNode__CDefinition",
+                "client::CNmGraphNode__CDefinition"
+            ]
+        );
+
+        let fields = index
+            .flattened_fields(&reference)
+            .into_iter()
+            .map(|(owner, field)| (owner.class_name, field.field_name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("CNmGraphNode__CDefinition".to_string(), "m_nNodeIdx"),
+                ("CNmPoseNode__CDefinition".to_string(), "m_flPlayTime"),
+            ]
+        );
+
+        let (reference, _) = index.find_class("CNmGraphNode__CDefinition").unwrap();
+        assert_eq!(index.inheritage.get_derived_classes(&reference).len(), 1);
+    }
+
+    #[test]
+    fn test_subclasses_cyclic() {
+        let schema = load_schema();
+        let index = SchemaIndex::new(&schema);
+
+        let (reference, _) = index.find_class("CCycleA").unwrap();
+        print_subclasses(
+            &index,
+            &reference,
+            1,
+            &mut BTreeSet::from([reference.clone()]),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search() {
+        let schema = load_schema();
+        let index = SchemaIndex::new(&schema);
+
+        let results = search(&index, "m_ihealth");
+        assert_eq!(
+            results,
+            vec![
+                "field  client::C_BaseEntity.m_iHealth = 0x10 (int32)",
+                "field  client::C_BasePlayerPawn.m_iMaxHealth = 0x8 (int32)",
+            ]
+        );
+
+        assert_eq!(
+            search(&index, "movetype"),
+            vec!["enum   client::MoveType_t"]
+        );
+        assert!(search(&index, "xyz").is_empty());
+    }
+
+    #[test]
+    fn test_find_enums() {
+        let schema = load_schema();
+        let index = SchemaIndex::new(&schema);
+
+        let enums = index.find_enums("client::MoveType_t").unwrap();
+        assert_eq!(enums.len(), 1);
+        assert_eq!(enums[0].0, "client");
+        assert_eq!(enums[0].1.memebers.len(), 2);
+
+        assert!(index.find_enums("server::MoveType_t").is_err());
+        assert!(index.find_enums("EUnknown").is_err());
+    }
+}
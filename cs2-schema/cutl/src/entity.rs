@@ -7,13 +7,22 @@ use std::{
     marker::PhantomData,
 };
 
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+
 /// CS2 32 bit entity handle packed with
 /// the entity index and serial number.
 #[repr(C)]
 #[derive(Default)]
 pub struct EntityHandle<T: ?Sized> {
     pub value: u32,
-    _data: PhantomData<T>,
+
+    /* the handle does not own a T, hence it is Send + Sync regardless of T */
+    _data: PhantomData<fn() -> *const T>,
 }
 
 impl<T: ?Sized> Clone for EntityHandle<T> {
@@ -71,4 +80,16 @@ impl<T: ?Sized> Hash for EntityHandle<T> {
     }
 }
 
+impl<T: ?Sized> Serialize for EntityHandle<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: ?Sized> Deserialize<'de> for EntityHandle<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(Self::from_index)
+    }
+}
+
 pub type CEntityIndex = u32;
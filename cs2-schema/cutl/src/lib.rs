@@ -9,3 +9,9 @@ pub use tier0::*;
 
 mod entity;
 pub use entity::*;
+
+mod mathlib;
+pub use mathlib::*;
+
+mod resource;
+pub use resource::*;
@@ -0,0 +1,37 @@
+use raw_struct::raw_struct;
+
+pub type Vector2D = [f32; 0x02];
+pub type Vector4D = [f32; 0x04];
+
+/// 16 byte aligned vector.
+/// The last component is padding.
+pub type VectorAligned = [f32; 0x04];
+
+pub type Quaternion = [f32; 0x04];
+pub type QuaternionStorage = [f32; 0x04];
+
+pub type RotationVector = [f32; 0x03];
+pub type RadianEuler = [f32; 0x03];
+pub type DegreeEuler = [f32; 0x03];
+
+/// Row major 3x4 matrix (matrix3x4_t)
+pub type Matrix3x4 = [[f32; 0x04]; 0x03];
+
+#[raw_struct(size = 0x20)]
+pub struct CTransform {
+    #[field(offset = 0x00)]
+    pub position: VectorAligned,
+
+    #[field(offset = 0x10)]
+    pub orientation: Quaternion,
+}
+
+/// 32 bit RGBA color
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
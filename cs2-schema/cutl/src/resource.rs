@@ -0,0 +1,14 @@
+use raw_struct::{
+    builtins::Ptr64,
+    raw_struct,
+};
+
+/// Binding of a resource loaded by the resource system.
+/// Referenced by `CStrongHandle<T>` and `CWeakHandle<T>`.
+#[raw_struct(size = 0x10)]
+pub struct CResourceBinding {
+    #[field(offset = 0x00)]
+    pub data: Ptr64<()>,
+}
+
+pub type CResourceHandle = Ptr64<dyn CResourceBinding>;
@@ -6,6 +6,7 @@ use raw_struct::{
 };
 
 use super::CUtlMemory;
+use crate::PtrCStr;
 
 #[raw_struct(size = 0x14)]
 pub struct CUtlString {
@@ -34,3 +35,12 @@ pub struct CUtlStringToken {
     // only present if compiled with DEBUG_STRINGTOKENS
     /* pub m_pDebugName: PtrCStr = 0x08 */
 }
+
+#[raw_struct(size = 0x10)]
+pub struct CUtlStringTokenWithStorage {
+    #[field(offset = 0x00)]
+    pub token: Copy<dyn CUtlStringToken>,
+
+    #[field(offset = 0x08)]
+    pub string: PtrCStr,
+}
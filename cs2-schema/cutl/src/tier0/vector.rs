@@ -1,6 +1,10 @@
+use std::marker;
+
 use raw_struct::{
     builtins::Ptr64,
     raw_struct,
+    AccessError,
+    MemoryView,
 };
 
 #[raw_struct(size = 0x10)]
@@ -14,3 +18,31 @@ where
     #[field(offset = 0x08)]
     pub data: Ptr64<[T]>,
 }
+
+impl<T: marker::Copy + Send + Sync + 'static> dyn CUtlVector<T> {
+    pub fn read_elements(&self, memory: &dyn MemoryView) -> Result<Vec<T>, AccessError> {
+        self.data()?.elements(memory, 0..self.size()? as usize)
+    }
+}
+
+/// Vector with a 32 bit element count, mainly used by the resource system
+#[raw_struct(size = 0x10)]
+pub struct CUtlLeanVector<T>
+where
+    T: Send + Sync + 'static,
+{
+    #[field(offset = 0x00)]
+    pub size: u32,
+
+    #[field(offset = 0x04)]
+    pub allocated: u32,
+
+    #[field(offset = 0x08)]
+    pub data: Ptr64<[T]>,
+}
+
+impl<T: marker::Copy + Send + Sync + 'static> dyn CUtlLeanVector<T> {
+    pub fn read_elements(&self, memory: &dyn MemoryView) -> Result<Vec<T>, AccessError> {
+        self.data()?.elements(memory, 0..self.size()? as usize)
+    }
+}
@@ -1,11 +1,6 @@
-use std::{
-    env,
-    fs::File,
-    io::BufReader,
-};
+use std::env;
 
-use anyhow::Context;
-use cs2_schema_definition::SchemaScope;
+use cs2_schema_definition::DumpedSchema;
 
 fn main() -> anyhow::Result<()> {
     let mut args = env::args().into_iter();
@@ -19,12 +14,12 @@ fn main() -> anyhow::Result<()> {
     };
 
     println!("Reading schema");
-    let mut reader = BufReader::new(File::open(src_file).context("open")?);
-    let schema_scopes =
-        serde_json::from_reader::<_, Vec<SchemaScope>>(&mut reader).context("parse schema")?;
+    let schema = DumpedSchema::from_file(&src_file)?;
 
     println!("Emitting Rust definition");
-    cs2_schema_definition::emit_to_dir(dst_dir, &schema_scopes)?;
+    for scope in cs2_schema_definition::emit_to_dir(dst_dir, &schema.scopes)? {
+        println!("  {:<24} {:>8} lines", scope.module_name, scope.lines);
+    }
 
     println!("Success");
     Ok(())
@@ -0,0 +1,188 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{
+        self,
+        Display,
+    },
+};
+
+use serde::Serialize;
+
+use crate::DumpedSchema;
+
+#[derive(Debug, Default, Serialize)]
+pub struct ScopeCoverage {
+    pub schema_name: String,
+    pub typed_fields: usize,
+    pub untyped_fields: usize,
+}
+
+/// Statistics of how many class fields have a generated Rust type
+/// and how many are only emitted as a comment.
+#[derive(Debug, Default, Serialize)]
+pub struct SchemaCoverage {
+    pub cs2_revision: String,
+    pub cs2_build_datetime: String,
+
+    pub typed_fields: usize,
+    pub untyped_fields: usize,
+
+    pub scopes: Vec<ScopeCoverage>,
+
+    /// C types (without template arguments) of the untyped fields
+    /// and their occurrences, most common first.
+    pub untyped_types: Vec<(String, usize)>,
+}
+
+impl SchemaCoverage {
+    pub fn compute(schema: &DumpedSchema) -> Self {
+        let mut result = Self {
+            cs2_revision: schema.cs2_revision.clone(),
+            cs2_build_datetime: schema.cs2_build_datetime.clone(),
+            ..Default::default()
+        };
+
+        let mut untyped_types = BTreeMap::<String, usize>::new();
+        for scope in schema.scopes.iter() {
+            let mut scope_coverage = ScopeCoverage {
+                schema_name: scope.schema_name.clone(),
+                ..Default::default()
+            };
+
+            for field in scope.classes.iter().flat_map(|class| class.offsets.iter()) {
+                if field.field_type.is_some() {
+                    scope_coverage.typed_fields += 1;
+                    continue;
+                }
+
+                scope_coverage.untyped_fields += 1;
+
+                let ctype = field
+                    .field_ctype
+                    .split_once('<')
+                    .map(|(name, _)| name)
+                    .unwrap_or(&field.field_ctype)
+                    .trim();
+                *untyped_types.entry(ctype.to_string()).or_default() += 1;
+            }
+
+            result.typed_fields += scope_coverage.typed_fields;
+            result.untyped_fields += scope_coverage.untyped_fields;
+            result.scopes.push(scope_coverage);
+        }
+
+        result.untyped_types = untyped_types.into_iter().collect();
+        result
+            .untyped_types
+            .sort_by(|(name_a, count_a), (name_b, count_b)| {
+                count_b.cmp(count_a).then_with(|| name_a.cmp(name_b))
+            });
+
+        result
+    }
+
+    pub fn total_fields(&self) -> usize {
+        self.typed_fields + self.untyped_fields
+    }
+
+    /// Percentage of typed fields
+    pub fn coverage(&self) -> f64 {
+        percentage(self.typed_fields, self.total_fields())
+    }
+}
+
+fn percentage(value: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        value as f64 * 100.0 / total as f64
+    }
+}
+
+impl Display for SchemaCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Schema coverage for CS2 version {} ({})",
+            self.cs2_revision, self.cs2_build_datetime
+        )?;
+        writeln!(
+            f,
+            "{} of {} fields typed ({:.1}%), {} commented out",
+            self.typed_fields,
+            self.total_fields(),
+            self.coverage(),
+            self.untyped_fields
+        )?;
+
+        writeln!(f)?;
+        writeln!(f, "Scopes:")?;
+        for scope in self.scopes.iter() {
+            writeln!(
+                f,
+                "  {:<24} {:>6} / {:<6} ({:.1}%)",
+                scope.schema_name,
+                scope.typed_fields,
+                scope.typed_fields + scope.untyped_fields,
+                percentage(
+                    scope.typed_fields,
+                    scope.typed_fields + scope.untyped_fields
+                )
+            )?;
+        }
+
+        if !self.untyped_types.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Untyped C types:")?;
+            for (ctype, count) in self.untyped_types.iter() {
+                writeln!(f, "  {:>6} {}", count, ctype)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SchemaCoverage;
+    use crate::DumpedSchema;
+
+    #[test]
+    fn coverage_fixture() {
+        let schema =
+            DumpedSchema::from_reader(include_str!("../fixtures/coverage.json").as_bytes())
+                .unwrap();
+        let coverage = SchemaCoverage::compute(&schema);
+
+        assert_eq!(coverage.cs2_revision, "10000");
+        assert_eq!(coverage.typed_fields, 3);
+        assert_eq!(coverage.untyped_fields, 3);
+        assert_eq!(coverage.total_fields(), 6);
+        assert_eq!(coverage.coverage(), 50.0);
+
+        assert_eq!(coverage.scopes.len(), 2);
+        assert_eq!(coverage.scopes[0].schema_name, "client.dll");
+        assert_eq!(coverage.scopes[0].typed_fields, 2);
+        assert_eq!(coverage.scopes[0].untyped_fields, 3);
+        assert_eq!(coverage.scopes[1].schema_name, "server.dll");
+        assert_eq!(coverage.scopes[1].typed_fields, 1);
+        assert_eq!(coverage.scopes[1].untyped_fields, 0);
+
+        /* template arguments are stripped, most common type first */
+        assert_eq!(
+            coverage.untyped_types,
+            vec![
+                ("CUtlVectorEmbeddedNetworkVar".to_string(), 2),
+                ("CUnknownType".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn coverage_empty() {
+        let coverage = SchemaCoverage::compute(&DumpedSchema::default());
+        assert_eq!(coverage.total_fields(), 0);
+        assert_eq!(coverage.coverage(), 100.0);
+    }
+}
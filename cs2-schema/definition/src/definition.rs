@@ -70,7 +70,164 @@ impl SchemaScope {
 pub enum Metadata {
     NetworkEnable,
     NetworkDisable,
-    NetworkChangeCallback { name: String },
-    NetworkVarNames { var_name: String, var_type: String },
-    Unknown { name: String },
+    NetworkChangeCallback {
+        name: String,
+    },
+    NetworkVarNames {
+        var_name: String,
+        var_type: String,
+    },
+    NetworkEncoder {
+        name: String,
+    },
+    NetworkSerializer {
+        name: String,
+    },
+    NetworkAlias {
+        name: String,
+    },
+    NetworkUserGroup {
+        name: String,
+    },
+    NetworkPriority {
+        priority: i32,
+    },
+    NetworkBitCount {
+        bits: i32,
+    },
+    NetworkEncodeFlags {
+        flags: i32,
+    },
+    NetworkMinValue {
+        value: f32,
+    },
+    NetworkMaxValue {
+        value: f32,
+    },
+    NetworkVarEmbeddedFieldOffsetDelta {
+        delta: i32,
+    },
+    PropertyFriendlyName {
+        name: String,
+    },
+    PropertyDescription {
+        description: String,
+    },
+
+    /// Metadata entry without a typed representation (or which failed to be read).
+    /// The value contains the first 8 bytes of the metadata value (little endian)
+    /// which may be an integer, a float or an address.
+    Unknown {
+        name: String,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "hex_bytes")]
+        value: Vec<u8>,
+    },
+}
+
+impl Metadata {
+    /// Name of the metadata entry within the CS2 schema system (e.g. `MNetworkEnable`)
+    pub fn name(&self) -> &str {
+        match self {
+            Self::NetworkEnable => "MNetworkEnable",
+            Self::NetworkDisable => "MNetworkDisable",
+            Self::NetworkChangeCallback { .. } => "MNetworkChangeCallback",
+            Self::NetworkVarNames { .. } => "MNetworkVarNames",
+            Self::NetworkEncoder { .. } => "MNetworkEncoder",
+            Self::NetworkSerializer { .. } => "MNetworkSerializer",
+            Self::NetworkAlias { .. } => "MNetworkAlias",
+            Self::NetworkUserGroup { .. } => "MNetworkUserGroup",
+            Self::NetworkPriority { .. } => "MNetworkPriority",
+            Self::NetworkBitCount { .. } => "MNetworkBitCount",
+            Self::NetworkEncodeFlags { .. } => "MNetworkEncodeFlags",
+            Self::NetworkMinValue { .. } => "MNetworkMinValue",
+            Self::NetworkMaxValue { .. } => "MNetworkMaxValue",
+            Self::NetworkVarEmbeddedFieldOffsetDelta { .. } => {
+                "MNetworkVarEmbeddedFieldOffsetDelta"
+            }
+            Self::PropertyFriendlyName { .. } => "MPropertyFriendlyName",
+            Self::PropertyDescription { .. } => "MPropertyDescription",
+            Self::Unknown { name, .. } => name,
+        }
+    }
+}
+
+/// Serialize raw bytes as a hex string to keep the schema file readable
+mod hex_bytes {
+    use std::fmt::Write;
+
+    use serde::{
+        de::Error,
+        Deserialize,
+        Deserializer,
+        Serializer,
+    };
+
+    pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut result = String::with_capacity(value.len() * 2);
+        for byte in value {
+            let _ = write!(&mut result, "{:02X}", byte);
+        }
+
+        serializer.serialize_str(&result)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        if value.len() % 2 != 0 {
+            return Err(D::Error::custom("hex string must have an even length"));
+        }
+
+        (0..value.len())
+            .step_by(2)
+            .map(|index| {
+                value
+                    .get(index..index + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or_else(|| D::Error::custom(format!("invalid hex string {}", value)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Metadata;
+
+    #[test]
+    fn metadata_roundtrip() {
+        let metadata = vec![
+            Metadata::NetworkPriority { priority: 32 },
+            Metadata::PropertyFriendlyName {
+                name: "Health".to_string(),
+            },
+            Metadata::Unknown {
+                name: "MNetworkIncludeByName".to_string(),
+                value: vec![0x00, 0xAB, 0x10],
+            },
+        ];
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"type":"NetworkPriority","priority":32},{"type":"PropertyFriendlyName","name":"Health"},{"type":"Unknown","name":"MNetworkIncludeByName","value":"00AB10"}]"#
+        );
+
+        let parsed = serde_json::from_str::<Vec<Metadata>>(&json).unwrap();
+        assert_eq!(parsed[2].name(), "MNetworkIncludeByName");
+        assert!(
+            matches!(&parsed[2], Metadata::Unknown { value, .. } if value == &[0x00, 0xAB, 0x10])
+        );
+    }
+
+    #[test]
+    fn metadata_unknown_without_value() {
+        /* schema files created before unknown metadata values have been captured */
+        let parsed = serde_json::from_str::<Metadata>(
+            r#"{"type":"Unknown","name":"MPropertySortPriority"}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(parsed, Metadata::Unknown { value, .. } if value.is_empty()));
+    }
 }
@@ -1,8 +1,3 @@
-use std::{
-    self,
-    borrow::Cow,
-};
-
 use serde::{
     Deserialize,
     Serialize,
@@ -10,6 +5,7 @@ use serde::{
 
 use crate::{
     mod_name_from_schema_name,
+    wrap_copy_type,
     ClassReference,
     EmitOutput,
     InheritageMap,
@@ -59,7 +55,7 @@ impl ClassDefinition {
         output.emit_line(&format!("}}"))?;
 
         for class in inheritage.get_inherited_classes(&ClassReference {
-            class_name: self.class_name.clone(),
+            class_name: class_name.clone(),
             module_name: mod_name_from_schema_name(&mod_name).to_string(),
         }) {
             output.emit_line(&format!(
@@ -110,11 +106,7 @@ impl ClassField {
             output.emit_line(&format!(
                 "pub {}: {},",
                 self.field_name,
-                if field_type.starts_with("dyn ") {
-                    Cow::from(format!("Copy<{}>", field_type))
-                } else {
-                    Cow::from(field_type)
-                }
+                wrap_copy_type(field_type)
             ))?;
         } else {
             output.emit_line(&format!(
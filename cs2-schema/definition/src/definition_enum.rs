@@ -27,6 +27,33 @@ pub struct EnumDefinition {
 }
 
 impl EnumDefinition {
+    /// Enums which represent a set of flags rather than a single value.
+    /// Flag enums get additional bit operation helpers.
+    pub fn is_flags(&self) -> bool {
+        let flags_metadata = self.metadata.iter().any(|metadata| {
+            matches!(metadata, Metadata::Unknown { name, .. } if name == "MEnumFlagsWithOverlappingBits")
+        });
+        if flags_metadata {
+            return true;
+        }
+
+        /*
+         * Without metadata every non zero member must be a single bit.
+         * Members sharing the value of another member are aliases (e.g. *_MAX).
+         * Sequential enums like 0, 1, 2 would match as well, therefore at least three bits are required.
+         */
+        let mut bits = 0u64;
+        for member in self.memebers.iter() {
+            if member.value != 0 && !member.value.is_power_of_two() {
+                return false;
+            }
+
+            bits |= member.value;
+        }
+
+        bits.count_ones() >= 3
+    }
+
     pub fn emit(&self, output: &mut dyn EmitOutput) -> anyhow::Result<()> {
         let enum_name = self.enum_name.replace(":", "_");
 
@@ -38,19 +65,187 @@ impl EnumDefinition {
             _ => anyhow::bail!("invalid enum size {}", self.enum_size),
         };
 
+        /* members sharing the same value are named after the first member */
+        let mut named_values = Vec::with_capacity(self.memebers.len());
+        for member in self.memebers.iter() {
+            let value = member.value & value_mask;
+            if !named_values.iter().any(|(_, known)| *known == value) {
+                named_values.push((&member.name, value));
+            }
+        }
+
         output.emit_line(&format!("/* enum {} ({}) */", enum_name, self.enum_name))?;
         output.emit_line(&format!("#[repr(transparent)]"))?;
-        output.emit_line(&format!("#[derive(Copy, Clone, Debug)]"))?;
+        output.emit_line(&format!("#[derive(Copy, Clone, PartialEq, Eq, Hash)]"))?;
         output.emit_line(&format!("pub struct {enum_name}({enum_type});"))?;
         output.emit_line(&format!("impl {enum_name} {{"))?;
         output.push_ident();
 
         self.memebers
             .iter()
-            .try_for_each(|offset| offset.emit(output, &enum_type, value_mask))?;
+            .try_for_each(|offset| offset.emit(output, &enum_name, value_mask))?;
+
+        output.emit_line("")?;
+        output.emit_line(&format!(
+            "pub const fn from_raw(value: {enum_type}) -> Self {{ Self(value) }}"
+        ))?;
+        output.emit_line(&format!(
+            "pub const fn raw(self) -> {enum_type} {{ self.0 }}"
+        ))?;
+
+        output.emit_line("")?;
+        output.emit_line("/// Name of the enum member matching the value")?;
+        output.emit_line("pub const fn name(self) -> Option<&'static str> {")?;
+        output.push_ident();
+        output.emit_line("match self.0 {")?;
+        output.push_ident();
+        for (name, value) in named_values.iter() {
+            output.emit_line(&format!("0x{value:X} => Some(\"{name}\"),"))?;
+        }
+        output.emit_line("_ => None,")?;
+        output.pop_ident();
+        output.emit_line("}")?;
+        output.pop_ident();
+        output.emit_line("}")?;
+
+        output.emit_line("")?;
+        output.emit_line("/// All known enum members")?;
+        output.emit_line("pub fn members() -> impl Iterator<Item = Self> {")?;
+        output.push_ident();
+        output.emit_line(&format!(
+            "[{}].into_iter()",
+            named_values
+                .iter()
+                .map(|(name, _)| format!("Self::{name}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))?;
+        output.pop_ident();
+        output.emit_line("}")?;
+
+        if self.is_flags() {
+            let all_bits = named_values
+                .iter()
+                .fold(0u64, |bits, (_, value)| bits | *value);
+
+            output.emit_line("")?;
+            output.emit_line(&format!(
+                "pub const ALL_BITS: {enum_type} = 0x{all_bits:X};"
+            ))?;
+            output.emit_line("pub const fn is_empty(self) -> bool { self.0 == 0 }")?;
+            output.emit_line(
+                "pub const fn contains(self, other: Self) -> bool { self.0 & other.0 == other.0 }",
+            )?;
+            output.emit_line(
+                "pub const fn intersects(self, other: Self) -> bool { self.0 & other.0 != 0 }",
+            )?;
+            output.emit_line("")?;
+            output.emit_line("/// All known flags set in this value")?;
+            output.emit_line("pub fn iter_set(self) -> impl Iterator<Item = Self> {")?;
+            output.push_ident();
+            output.emit_line(
+                "Self::members().filter(move |flag| flag.0 != 0 && self.contains(*flag))",
+            )?;
+            output.pop_ident();
+            output.emit_line("}")?;
+        }
 
         output.pop_ident();
         output.emit_line(&format!("}}"))?;
+
+        output.emit_line(&format!("impl TryFrom<{enum_type}> for {enum_name} {{"))?;
+        output.push_ident();
+        output.emit_line(&format!("type Error = {enum_type};"))?;
+        output.emit_line("")?;
+        output.emit_line(&format!(
+            "fn try_from(value: {enum_type}) -> Result<Self, Self::Error> {{"
+        ))?;
+        output.push_ident();
+        if self.is_flags() {
+            output.emit_line(
+                "if value & !Self::ALL_BITS == 0 { Ok(Self(value)) } else { Err(value) }",
+            )?;
+        } else {
+            output.emit_line(
+                "if Self(value).name().is_some() { Ok(Self(value)) } else { Err(value) }",
+            )?;
+        }
+        output.pop_ident();
+        output.emit_line("}")?;
+        output.pop_ident();
+        output.emit_line("}")?;
+
+        output.emit_line(&format!("impl core::fmt::Display for {enum_name} {{"))?;
+        output.push_ident();
+        output
+            .emit_line("fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {")?;
+        output.push_ident();
+        if self.is_flags() {
+            output.emit_line("if let Some(name) = self.name() {")?;
+            output.emit_line("    return f.write_str(name);")?;
+            output.emit_line("}")?;
+            output.emit_line("")?;
+            output.emit_line("let mut remaining = self.0;")?;
+            output.emit_line("for flag in self.iter_set() {")?;
+            output.emit_line("    if remaining != self.0 { f.write_str(\" | \")?; }")?;
+            output.emit_line("    f.write_str(flag.name().unwrap_or_default())?;")?;
+            output.emit_line("    remaining &= !flag.0;")?;
+            output.emit_line("}")?;
+            output.emit_line("")?;
+            output.emit_line("if remaining != 0 {")?;
+            output.emit_line("    if remaining != self.0 { f.write_str(\" | \")?; }")?;
+            output.emit_line("    write!(f, \"0x{:X}\", remaining)")?;
+            output.emit_line("} else if self.0 == 0 {")?;
+            output.emit_line("    f.write_str(\"0x0\")")?;
+            output.emit_line("} else {")?;
+            output.emit_line("    Ok(())")?;
+            output.emit_line("}")?;
+        } else {
+            output.emit_line("match self.name() {")?;
+            output.emit_line("    Some(name) => f.write_str(name),")?;
+            output.emit_line("    None => write!(f, \"0x{:X}\", self.0),")?;
+            output.emit_line("}")?;
+        }
+        output.pop_ident();
+        output.emit_line("}")?;
+        output.pop_ident();
+        output.emit_line("}")?;
+
+        output.emit_line(&format!("impl core::fmt::Debug for {enum_name} {{"))?;
+        output.push_ident();
+        output
+            .emit_line("fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {")?;
+        output.push_ident();
+        output.emit_line(&format!("write!(f, \"{enum_name}({{}})\", self)"))?;
+        output.pop_ident();
+        output.emit_line("}")?;
+        output.pop_ident();
+        output.emit_line("}")?;
+
+        if self.is_flags() {
+            for (op_trait, op_fn, op) in [("BitOr", "bitor", "|"), ("BitAnd", "bitand", "&")] {
+                output.emit_line(&format!("impl core::ops::{op_trait} for {enum_name} {{"))?;
+                output.push_ident();
+                output.emit_line("type Output = Self;")?;
+                output.emit_line("")?;
+                output.emit_line(&format!(
+                    "fn {op_fn}(self, other: Self) -> Self {{ Self(self.0 {op} other.0) }}"
+                ))?;
+                output.pop_ident();
+                output.emit_line("}")?;
+
+                output.emit_line(&format!(
+                    "impl core::ops::{op_trait}Assign for {enum_name} {{"
+                ))?;
+                output.push_ident();
+                output.emit_line(&format!(
+                    "fn {op_fn}_assign(&mut self, other: Self) {{ self.0 {op}= other.0; }}"
+                ))?;
+                output.pop_ident();
+                output.emit_line("}")?;
+            }
+        }
+
         output.emit_line(&format!("/* {} */", self.enum_name))?;
 
         Ok(())
@@ -67,15 +262,79 @@ impl EnumMember {
     fn emit(
         &self,
         output: &mut dyn EmitOutput,
-        rtype: &str,
+        enum_name: &str,
         value_mask: u64,
     ) -> anyhow::Result<()> {
         let member_name = &self.name;
         let member_value = self.value & value_mask;
 
         output.emit_line(&format!(
-            "const {member_name}: {rtype} = 0x{member_value:X};"
+            "pub const {member_name}: {enum_name} = {enum_name}(0x{member_value:X});"
         ))?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        DumpedSchema,
+        EnumDefinition,
+        EnumMember,
+        InheritageMap,
+        Metadata,
+        StringEmitter,
+    };
+
+    fn create_enum(enum_name: &str, values: &[u64]) -> EnumDefinition {
+        EnumDefinition {
+            enum_name: enum_name.to_string(),
+            enum_size: 4,
+            memebers: values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| EnumMember {
+                    name: format!("MEMBER_{}", index),
+                    value: *value,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flags_detection() {
+        assert!(create_enum("EBits", &[0, 1, 2, 4, 4]).is_flags());
+        assert!(!create_enum("ESequential", &[0, 1, 2, 3]).is_flags());
+
+        /* the name alone does not make an enum a flags enum */
+        assert!(!create_enum("EFlagState", &[0, 1, 2]).is_flags());
+        assert!(!create_enum("EMixedFlags", &[1, 2, 4, 6]).is_flags());
+
+        let mut overlapping = create_enum("EOverlapping", &[1, 3]);
+        overlapping.metadata.push(Metadata::Unknown {
+            name: "MEnumFlagsWithOverlappingBits".to_string(),
+            value: Vec::new(),
+        });
+        assert!(overlapping.is_flags());
+    }
+
+    #[test]
+    fn enums_fixture() {
+        let schema =
+            DumpedSchema::from_reader(include_str!("../fixtures/enums.json").as_bytes()).unwrap();
+
+        let inheritage = InheritageMap::build(&schema.scopes);
+        let mut output = StringEmitter::new();
+        for scope in schema.scopes.iter() {
+            scope
+                .emit_rust_definition(&mut output, &inheritage)
+                .unwrap();
+        }
+
+        assert_eq!(
+            output.into_string(),
+            include_str!("../fixtures/enums.rs.snap").replace("\r\n", "\n")
+        );
+    }
+}
@@ -0,0 +1,706 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fmt,
+};
+
+use serde::Serialize;
+
+use crate::{
+    ClassDefinition,
+    ClassField,
+    DumpedSchema,
+    EnumDefinition,
+};
+
+/// Identifies a class field the same way `runtime_offset!` does:
+/// schema scope name, class name and field name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct FieldReference {
+    pub schema_name: String,
+    pub class_name: String,
+    pub field_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct SchemaItemReference {
+    pub schema_name: String,
+    pub name: String,
+}
+
+impl fmt::Display for SchemaItemReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.schema_name, self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValueChange<T> {
+    pub old: T,
+    pub new: T,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ClassDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_size: Option<ValueChange<u64>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherits: Option<ValueChange<Option<String>>>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields_added: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields_removed: Vec<String>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields_moved: BTreeMap<String, ValueChange<u64>>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields_retyped: BTreeMap<String, ValueChange<String>>,
+}
+
+impl ClassDiff {
+    pub fn is_empty(&self) -> bool {
+        self.class_size.is_none()
+            && self.inherits.is_none()
+            && self.fields_added.is_empty()
+            && self.fields_removed.is_empty()
+            && self.fields_moved.is_empty()
+            && self.fields_retyped.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct EnumDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enum_size: Option<ValueChange<usize>>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members_added: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members_removed: Vec<String>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub members_changed: BTreeMap<String, ValueChange<u64>>,
+}
+
+impl EnumDiff {
+    pub fn is_empty(&self) -> bool {
+        self.enum_size.is_none()
+            && self.members_added.is_empty()
+            && self.members_removed.is_empty()
+            && self.members_changed.is_empty()
+    }
+}
+
+/// Differences between two schema dumps
+#[derive(Debug, Default, Serialize)]
+pub struct SchemaDiff {
+    pub old_revision: String,
+    pub new_revision: String,
+
+    pub classes_added: Vec<SchemaItemReference>,
+    pub classes_removed: Vec<SchemaItemReference>,
+    pub classes_changed: BTreeMap<String, ClassDiff>,
+
+    pub enums_added: Vec<SchemaItemReference>,
+    pub enums_removed: Vec<SchemaItemReference>,
+    pub enums_changed: BTreeMap<String, EnumDiff>,
+}
+
+fn index_classes(schema: &DumpedSchema) -> BTreeMap<SchemaItemReference, &ClassDefinition> {
+    schema
+        .scopes
+        .iter()
+        .flat_map(|scope| {
+            scope.classes.iter().map(|class| {
+                (
+                    SchemaItemReference {
+                        schema_name: scope.schema_name.clone(),
+                        name: class.class_name.clone(),
+                    },
+                    class,
+                )
+            })
+        })
+        .collect()
+}
+
+fn index_enums(schema: &DumpedSchema) -> BTreeMap<SchemaItemReference, &EnumDefinition> {
+    schema
+        .scopes
+        .iter()
+        .flat_map(|scope| {
+            scope.enums.iter().map(|definition| {
+                (
+                    SchemaItemReference {
+                        schema_name: scope.schema_name.clone(),
+                        name: definition.enum_name.clone(),
+                    },
+                    definition,
+                )
+            })
+        })
+        .collect()
+}
+
+fn index_fields(class: &ClassDefinition) -> BTreeMap<&str, &ClassField> {
+    class
+        .offsets
+        .iter()
+        .map(|field| (field.field_name.as_str(), field))
+        .collect()
+}
+
+fn diff_class(
+    reference: &SchemaItemReference,
+    old: &ClassDefinition,
+    new: &ClassDefinition,
+    field_filter: &dyn Fn(&FieldReference) -> bool,
+) -> ClassDiff {
+    let mut result = ClassDiff::default();
+
+    if old.class_size != new.class_size {
+        result.class_size = Some(ValueChange {
+            old: old.class_size,
+            new: new.class_size,
+        });
+    }
+
+    if old.inherits != new.inherits {
+        result.inherits = Some(ValueChange {
+            old: old.inherits.clone(),
+            new: new.inherits.clone(),
+        });
+    }
+
+    let old_fields = index_fields(old);
+    let new_fields = index_fields(new);
+    let field_included = |field_name: &str| {
+        field_filter(&FieldReference {
+            schema_name: reference.schema_name.clone(),
+            class_name: reference.name.clone(),
+            field_name: field_name.to_string(),
+        })
+    };
+
+    for (field_name, old_field) in old_fields.iter() {
+        if !field_included(field_name) {
+            continue;
+        }
+
+        let Some(new_field) = new_fields.get(field_name) else {
+            result.fields_removed.push(field_name.to_string());
+            continue;
+        };
+
+        if old_field.offset != new_field.offset {
+            result.fields_moved.insert(
+                field_name.to_string(),
+                ValueChange {
+                    old: old_field.offset,
+                    new: new_field.offset,
+                },
+            );
+        }
+
+        if old_field.field_ctype != new_field.field_ctype {
+            result.fields_retyped.insert(
+                field_name.to_string(),
+                ValueChange {
+                    old: old_field.field_ctype.clone(),
+                    new: new_field.field_ctype.clone(),
+                },
+            );
+        }
+    }
+
+    for field_name in new_fields.keys() {
+        if !old_fields.contains_key(field_name) && field_included(field_name) {
+            result.fields_added.push(field_name.to_string());
+        }
+    }
+
+    result
+}
+
+fn diff_enum(old: &EnumDefinition, new: &EnumDefinition) -> EnumDiff {
+    let mut result = EnumDiff::default();
+
+    if old.enum_size != new.enum_size {
+        result.enum_size = Some(ValueChange {
+            old: old.enum_size,
+            new: new.enum_size,
+        });
+    }
+
+    let old_members = old
+        .memebers
+        .iter()
+        .map(|member| (member.name.as_str(), member.value))
+        .collect::<BTreeMap<_, _>>();
+    let new_members = new
+        .memebers
+        .iter()
+        .map(|member| (member.name.as_str(), member.value))
+        .collect::<BTreeMap<_, _>>();
+
+    for (name, old_value) in old_members.iter() {
+        match new_members.get(name) {
+            Some(new_value) if new_value != old_value => {
+                result.members_changed.insert(
+                    name.to_string(),
+                    ValueChange {
+                        old: *old_value,
+                        new: *new_value,
+                    },
+                );
+            }
+            Some(_) => {}
+            None => result.members_removed.push(name.to_string()),
+        }
+    }
+
+    for name in new_members.keys() {
+        if !old_members.contains_key(name) {
+            result.members_added.push(name.to_string());
+        }
+    }
+
+    result
+}
+
+/// Whether any field of the class passes the filter
+fn class_included(
+    reference: &SchemaItemReference,
+    class: &ClassDefinition,
+    field_filter: &dyn Fn(&FieldReference) -> bool,
+) -> bool {
+    class.offsets.iter().any(|field| {
+        field_filter(&FieldReference {
+            schema_name: reference.schema_name.clone(),
+            class_name: reference.name.clone(),
+            field_name: field.field_name.clone(),
+        })
+    })
+}
+
+/// Collect the identifiers of all C types used by fields which pass the filter.
+/// Template arguments and array types are split up, so `CNetworkVarBase<MoveType_t>`
+/// references `CNetworkVarBase` and `MoveType_t`.
+fn collect_referenced_types(
+    schema: &DumpedSchema,
+    field_filter: &dyn Fn(&FieldReference) -> bool,
+    result: &mut BTreeSet<String>,
+) {
+    for (reference, class) in index_classes(schema) {
+        for field in class.offsets.iter() {
+            let included = field_filter(&FieldReference {
+                schema_name: reference.schema_name.clone(),
+                class_name: reference.name.clone(),
+                field_name: field.field_name.clone(),
+            });
+            if !included {
+                continue;
+            }
+
+            result.extend(
+                field
+                    .field_ctype
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .filter(|identifier| !identifier.is_empty())
+                    .map(str::to_string),
+            );
+        }
+    }
+}
+
+impl SchemaDiff {
+    /// Compare two schema dumps
+    pub fn compute(old: &DumpedSchema, new: &DumpedSchema) -> Self {
+        Self::compute_inner(old, new, None)
+    }
+
+    /// Compare two schema dumps but only report changes relevant for fields
+    /// where `field_filter` returns true:
+    /// - Classes are only reported if they contain a field passing the filter.
+    /// - Changed classes which only differ in filtered out fields will not be reported.
+    /// - Enums are only reported if they are used as type of a field passing the filter.
+    pub fn compute_filtered(
+        old: &DumpedSchema,
+        new: &DumpedSchema,
+        field_filter: &dyn Fn(&FieldReference) -> bool,
+    ) -> Self {
+        Self::compute_inner(old, new, Some(field_filter))
+    }
+
+    fn compute_inner(
+        old: &DumpedSchema,
+        new: &DumpedSchema,
+        field_filter: Option<&dyn Fn(&FieldReference) -> bool>,
+    ) -> Self {
+        let mut result = Self {
+            old_revision: old.cs2_revision.clone(),
+            new_revision: new.cs2_revision.clone(),
+            ..Default::default()
+        };
+
+        let old_classes = index_classes(old);
+        let new_classes = index_classes(new);
+        let class_included = |reference: &SchemaItemReference, class: &ClassDefinition| {
+            field_filter.is_none_or(|filter| class_included(reference, class, filter))
+        };
+
+        for (reference, old_class) in old_classes.iter() {
+            let Some(new_class) = new_classes.get(reference) else {
+                if class_included(reference, old_class) {
+                    result.classes_removed.push(reference.clone());
+                }
+                continue;
+            };
+
+            if !class_included(reference, old_class) && !class_included(reference, new_class) {
+                continue;
+            }
+
+            let diff = diff_class(
+                reference,
+                old_class,
+                new_class,
+                field_filter.unwrap_or(&|_| true),
+            );
+            if !diff.is_empty() {
+                result.classes_changed.insert(reference.to_string(), diff);
+            }
+        }
+
+        for (reference, new_class) in new_classes.iter() {
+            if !old_classes.contains_key(reference) && class_included(reference, new_class) {
+                result.classes_added.push(reference.clone());
+            }
+        }
+
+        let referenced_types = field_filter.map(|filter| {
+            let mut referenced_types = BTreeSet::new();
+            collect_referenced_types(old, filter, &mut referenced_types);
+            collect_referenced_types(new, filter, &mut referenced_types);
+            referenced_types
+        });
+        let enum_included = |reference: &SchemaItemReference| {
+            referenced_types
+                .as_ref()
+                .is_none_or(|types| types.contains(&reference.name))
+        };
+
+        let old_enums = index_enums(old);
+        let new_enums = index_enums(new);
+        for (reference, old_enum) in old_enums.iter() {
+            if !enum_included(reference) {
+                continue;
+            }
+
+            let Some(new_enum) = new_enums.get(reference) else {
+                result.enums_removed.push(reference.clone());
+                continue;
+            };
+
+            let diff = diff_enum(old_enum, new_enum);
+            if !diff.is_empty() {
+                result.enums_changed.insert(reference.to_string(), diff);
+            }
+        }
+
+        for reference in new_enums.keys() {
+            if !old_enums.contains_key(reference) && enum_included(reference) {
+                result.enums_added.push(reference.clone());
+            }
+        }
+
+        result
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes_added.is_empty()
+            && self.classes_removed.is_empty()
+            && self.classes_changed.is_empty()
+            && self.enums_added.is_empty()
+            && self.enums_removed.is_empty()
+            && self.enums_changed.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Schema diff {} -> {}",
+            if self.old_revision.is_empty() {
+                "unknown"
+            } else {
+                &self.old_revision
+            },
+            if self.new_revision.is_empty() {
+                "unknown"
+            } else {
+                &self.new_revision
+            }
+        )?;
+
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+
+        for reference in self.classes_added.iter() {
+            writeln!(f, "+ class {}", reference)?;
+        }
+
+        for reference in self.classes_removed.iter() {
+            writeln!(f, "- class {}", reference)?;
+        }
+
+        for (name, diff) in self.classes_changed.iter() {
+            writeln!(f, "~ class {}", name)?;
+            if let Some(change) = &diff.class_size {
+                writeln!(f, "    size 0x{:X} -> 0x{:X}", change.old, change.new)?;
+            }
+
+            if let Some(change) = &diff.inherits {
+                writeln!(
+                    f,
+                    "    inherits {} -> {}",
+                    change.old.as_deref().unwrap_or("<none>"),
+                    change.new.as_deref().unwrap_or("<none>")
+                )?;
+            }
+
+            for field in diff.fields_added.iter() {
+                writeln!(f, "    + {}", field)?;
+            }
+
+            for field in diff.fields_removed.iter() {
+                writeln!(f, "    - {}", field)?;
+            }
+
+            for (field, change) in diff.fields_moved.iter() {
+                writeln!(f, "    {} 0x{:X} -> 0x{:X}", field, change.old, change.new)?;
+            }
+
+            for (field, change) in diff.fields_retyped.iter() {
+                writeln!(f, "    {}: {} -> {}", field, change.old, change.new)?;
+            }
+        }
+
+        for reference in self.enums_added.iter() {
+            writeln!(f, "+ enum {}", reference)?;
+        }
+
+        for reference in self.enums_removed.iter() {
+            writeln!(f, "- enum {}", reference)?;
+        }
+
+        for (name, diff) in self.enums_changed.iter() {
+            writeln!(f, "~ enum {}", name)?;
+            if let Some(change) = &diff.enum_size {
+                writeln!(f, "    size {} -> {}", change.old, change.new)?;
+            }
+
+            for member in diff.members_added.iter() {
+                writeln!(f, "    + {}", member)?;
+            }
+
+            for member in diff.members_removed.iter() {
+                writeln!(f, "    - {}", member)?;
+            }
+
+            for (member, change) in diff.members_changed.iter() {
+                writeln!(f, "    {} 0x{:X} -> 0x{:X}", member, change.old, change.new)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Collect all fields which are accessed via `runtime_offset!` by the generated bindings.
+/// Only fields with a mapped Rust type will be emitted by the generator.
+pub fn collect_bound_fields(schema: &DumpedSchema) -> BTreeSet<FieldReference> {
+    schema
+        .scopes
+        .iter()
+        .flat_map(|scope| {
+            scope.classes.iter().flat_map(move |class| {
+                class
+                    .offsets
+                    .iter()
+                    .filter(|field| field.field_type.is_some())
+                    .map(move |field| FieldReference {
+                        schema_name: scope.schema_name.clone(),
+                        class_name: class.class_name.clone(),
+                        field_name: field.field_name.clone(),
+                    })
+            })
+        })
+        .collect()
+}
+
+/// Collect all `runtime_offset!` call sites of generated Rust bindings
+pub fn collect_runtime_offset_calls(source: &str) -> BTreeSet<FieldReference> {
+    const MACRO_NAME: &str = "runtime_offset!(";
+
+    let mut result = BTreeSet::new();
+    for (index, _) in source.match_indices(MACRO_NAME) {
+        let arguments = &source[index + MACRO_NAME.len()..];
+        let Some(end) = arguments.find(')') else {
+            continue;
+        };
+
+        /* runtime_offset!(<default offset>, "<module>", "<class>", "<member>") */
+        let arguments = arguments[..end]
+            .split(',')
+            .map(|argument| argument.trim().trim_matches('"'))
+            .collect::<Vec<_>>();
+
+        if let [_, schema_name, class_name, field_name] = arguments.as_slice() {
+            result.insert(FieldReference {
+                schema_name: schema_name.to_string(),
+                class_name: class_name.to_string(),
+                field_name: field_name.to_string(),
+            });
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        FieldReference,
+        SchemaDiff,
+        SchemaItemReference,
+        ValueChange,
+    };
+    use crate::DumpedSchema;
+
+    fn load_fixtures() -> (DumpedSchema, DumpedSchema) {
+        (
+            DumpedSchema::from_reader(include_str!("../fixtures/diff_old.json").as_bytes())
+                .unwrap(),
+            DumpedSchema::from_reader(include_str!("../fixtures/diff_new.json").as_bytes())
+                .unwrap(),
+        )
+    }
+
+    fn item(name: &str) -> SchemaItemReference {
+        SchemaItemReference {
+            schema_name: "client.dll".to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    fn field(class_name: &str, field_name: &str) -> FieldReference {
+        FieldReference {
+            schema_name: "client.dll".to_string(),
+            class_name: class_name.to_string(),
+            field_name: field_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn diff_classes() {
+        let (old, new) = load_fixtures();
+        let diff = SchemaDiff::compute(&old, &new);
+
+        assert_eq!(diff.old_revision, "10000");
+        assert_eq!(diff.new_revision, "10001");
+        assert_eq!(diff.classes_added, vec![item("C_Added")]);
+        assert_eq!(diff.classes_removed, vec![item("C_Removed")]);
+        assert_eq!(
+            diff.classes_changed.keys().collect::<Vec<_>>(),
+            vec!["client.dll::C_BaseEntity", "client.dll::C_Unused"]
+        );
+
+        let class = &diff.classes_changed["client.dll::C_BaseEntity"];
+        assert_eq!(class.class_size, Some(ValueChange { old: 64, new: 72 }));
+        assert_eq!(class.fields_added, vec!["m_nAdded"]);
+        assert_eq!(class.fields_removed, vec!["m_nRemoved"]);
+        assert_eq!(class.fields_moved.len(), 2);
+        assert_eq!(
+            class.fields_moved["m_iHealth"],
+            ValueChange { old: 16, new: 24 }
+        );
+        assert!(class.fields_retyped.is_empty());
+    }
+
+    #[test]
+    fn diff_enums() {
+        let (old, new) = load_fixtures();
+        let diff = SchemaDiff::compute(&old, &new);
+
+        assert_eq!(diff.enums_added, vec![item("EAdded")]);
+        assert_eq!(diff.enums_removed, vec![item("ERemoved")]);
+
+        let definition = &diff.enums_changed["client.dll::MoveType_t"];
+        assert!(definition.enum_size.is_none());
+        assert_eq!(definition.members_added, vec!["MOVETYPE_LADDER"]);
+        assert_eq!(definition.members_removed, vec!["MOVETYPE_FLY"]);
+        assert_eq!(
+            definition.members_changed["MOVETYPE_WALK"],
+            ValueChange { old: 1, new: 2 }
+        );
+    }
+
+    #[test]
+    fn diff_filtered() {
+        let (old, new) = load_fixtures();
+        let used_fields = [
+            field("C_BaseEntity", "m_iHealth"),
+            field("C_BaseEntity", "m_MoveType"),
+            field("C_BaseEntity", "m_nRemoved"),
+        ];
+        let diff = SchemaDiff::compute_filtered(&old, &new, &|field| used_fields.contains(field));
+
+        /* neither C_Added nor C_Removed contain any used field */
+        assert!(diff.classes_added.is_empty());
+        assert!(diff.classes_removed.is_empty());
+        assert_eq!(
+            diff.classes_changed.keys().collect::<Vec<_>>(),
+            vec!["client.dll::C_BaseEntity"]
+        );
+
+        let class = &diff.classes_changed["client.dll::C_BaseEntity"];
+        assert!(class.fields_added.is_empty());
+        assert_eq!(class.fields_removed, vec!["m_nRemoved"]);
+        assert_eq!(
+            class.fields_moved.keys().collect::<Vec<_>>(),
+            vec!["m_iHealth"]
+        );
+
+        /* only MoveType_t is referenced by a used field */
+        assert!(diff.enums_added.is_empty());
+        assert!(diff.enums_removed.is_empty());
+        assert_eq!(
+            diff.enums_changed.keys().collect::<Vec<_>>(),
+            vec!["client.dll::MoveType_t"]
+        );
+    }
+
+    #[test]
+    fn diff_filtered_removed_class() {
+        let (old, new) = load_fixtures();
+        let used_fields = [field("C_Removed", "m_nValue")];
+
+        /* C_BaseEntity changed in size but does not contain any used field */
+        let diff = SchemaDiff::compute_filtered(&old, &new, &|field| used_fields.contains(field));
+
+        assert_eq!(diff.classes_removed, vec![item("C_Removed")]);
+        assert!(diff.classes_added.is_empty());
+        assert!(diff.classes_changed.is_empty());
+        assert!(diff.enums_changed.is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{
+    mod_name_from_schema_name,
+    DumpedSchema,
+    EmitOutput,
+};
+
+/// Emit a C/C++ header defining all schema field offsets as constants.
+///
+/// Offsets are named `<module>_<class>_<field>`, module offsets `<module offset>_OFFSET`.
+pub fn emit_c_header(schema: &DumpedSchema, output: &mut dyn EmitOutput) -> anyhow::Result<()> {
+    output.emit_line("#pragma once")?;
+    output.emit_line("")?;
+    output.emit_line("/*")?;
+    output.emit_line(" * CS2 schema offsets")?;
+    output.emit_line(&format!(" * Revision: {}", schema.cs2_revision))?;
+    output.emit_line(&format!(" * Build: {}", schema.cs2_build_datetime))?;
+    output.emit_line(" */")?;
+    output.emit_line("")?;
+
+    if !schema.resolved_offsets.is_empty() {
+        output.emit_line("/* module offsets */")?;
+        for (name, value) in schema.resolved_offsets.iter() {
+            output.emit_line(&format!(
+                "#define {}_OFFSET 0x{:X}",
+                name.replace("-", "_").to_uppercase(),
+                value
+            ))?;
+        }
+        output.emit_line("")?;
+    }
+
+    for scope in schema.scopes.iter() {
+        let mod_name = mod_name_from_schema_name(&scope.schema_name);
+        for class in scope.classes.iter() {
+            let class_name = class.class_name.replace(":", "_");
+            match &class.inherits {
+                Some(inherits) => output.emit_line(&format!(
+                    "/* class {} ({}), size 0x{:X}, inherits {} */",
+                    class_name, scope.schema_name, class.class_size, inherits
+                ))?,
+                None => output.emit_line(&format!(
+                    "/* class {} ({}), size 0x{:X} */",
+                    class_name, scope.schema_name, class.class_size
+                ))?,
+            }
+
+            for field in class.offsets.iter() {
+                output.emit_line(&format!(
+                    "#define {}_{}_{} 0x{:X} /* {} */",
+                    mod_name, class_name, field.field_name, field.offset, field.field_ctype
+                ))?;
+            }
+            output.emit_line("")?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        DumpedSchema,
+        StringEmitter,
+    };
+
+    #[test]
+    fn c_header_fixture() {
+        let schema =
+            DumpedSchema::from_reader(include_str!("../fixtures/export.json").as_bytes()).unwrap();
+
+        let mut output = StringEmitter::new();
+        super::emit_c_header(&schema, &mut output).unwrap();
+
+        assert_eq!(
+            output.into_string(),
+            include_str!("../fixtures/export.h.snap").replace("\r\n", "\n")
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    mod_name_from_schema_name,
+    DumpedSchema,
+    EmitOutput,
+};
+
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Emit a C# file containing a static class per schema scope
+/// with a nested static class of field offsets for each schema class.
+pub fn emit_csharp(schema: &DumpedSchema, output: &mut dyn EmitOutput) -> anyhow::Result<()> {
+    output.emit_line("// CS2 schema offsets")?;
+    output.emit_line(&format!("// Revision: {}", schema.cs2_revision))?;
+    output.emit_line(&format!("// Build: {}", schema.cs2_build_datetime))?;
+    output.emit_line("namespace CS2Schema")?;
+    output.emit_line("{")?;
+    output.push_ident();
+
+    if !schema.resolved_offsets.is_empty() {
+        output.emit_line("public static class ModuleOffsets")?;
+        output.emit_line("{")?;
+        output.push_ident();
+        for (name, value) in schema.resolved_offsets.iter() {
+            output.emit_line(&format!(
+                "public const ulong {} = 0x{:X};",
+                pascal_case(name),
+                value
+            ))?;
+        }
+        output.pop_ident();
+        output.emit_line("}")?;
+    }
+
+    for scope in schema.scopes.iter() {
+        output.emit_line(&format!(
+            "public static class {}",
+            pascal_case(mod_name_from_schema_name(&scope.schema_name))
+        ))?;
+        output.emit_line("{")?;
+        output.push_ident();
+
+        for class in scope.classes.iter() {
+            output.emit_line(&format!(
+                "// size 0x{:X}{}",
+                class.class_size,
+                class
+                    .inherits
+                    .as_ref()
+                    .map(|inherits| format!(", inherits {}", inherits))
+                    .unwrap_or_default()
+            ))?;
+            output.emit_line(&format!(
+                "public static class {}",
+                class.class_name.replace(":", "_")
+            ))?;
+            output.emit_line("{")?;
+            output.push_ident();
+            for field in class.offsets.iter() {
+                output.emit_line(&format!(
+                    "public const int {} = 0x{:X}; // {}",
+                    field.field_name, field.offset, field.field_ctype
+                ))?;
+            }
+            output.pop_ident();
+            output.emit_line("}")?;
+        }
+
+        output.pop_ident();
+        output.emit_line("}")?;
+    }
+
+    output.pop_ident();
+    output.emit_line("}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        DumpedSchema,
+        StringEmitter,
+    };
+
+    #[test]
+    fn csharp_fixture() {
+        let schema =
+            DumpedSchema::from_reader(include_str!("../fixtures/export.json").as_bytes()).unwrap();
+
+        let mut output = StringEmitter::new();
+        super::emit_csharp(&schema, &mut output).unwrap();
+
+        assert_eq!(
+            output.into_string(),
+            include_str!("../fixtures/export.cs.snap").replace("\r\n", "\n")
+        );
+    }
+}
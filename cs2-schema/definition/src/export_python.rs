@@ -0,0 +1,81 @@
+use crate::{
+    mod_name_from_schema_name,
+    DumpedSchema,
+    EmitOutput,
+};
+
+/// Emit a Python module containing a class per schema scope
+/// with a nested class of field offsets for each schema class.
+pub fn emit_python(schema: &DumpedSchema, output: &mut dyn EmitOutput) -> anyhow::Result<()> {
+    output.emit_line("# CS2 schema offsets")?;
+    output.emit_line(&format!("# Revision: {}", schema.cs2_revision))?;
+    output.emit_line(&format!("# Build: {}", schema.cs2_build_datetime))?;
+    output.emit_line("")?;
+
+    output.emit_line("MODULE_OFFSETS = {")?;
+    output.push_ident();
+    for (name, value) in schema.resolved_offsets.iter() {
+        output.emit_line(&format!("\"{}\": 0x{:X},", name, value))?;
+    }
+    output.pop_ident();
+    output.emit_line("}")?;
+
+    for scope in schema.scopes.iter() {
+        output.emit_line("")?;
+        output.emit_line("")?;
+        output.emit_line(&format!(
+            "class {}:",
+            mod_name_from_schema_name(&scope.schema_name)
+        ))?;
+        output.push_ident();
+        output.emit_line(&format!("\"\"\"{}\"\"\"", scope.schema_name))?;
+
+        for class in scope.classes.iter() {
+            output.emit_line("")?;
+            output.emit_line(&format!("class {}:", class.class_name.replace(":", "_")))?;
+            output.push_ident();
+            output.emit_line(&format!(
+                "\"\"\"size 0x{:X}{}\"\"\"",
+                class.class_size,
+                class
+                    .inherits
+                    .as_ref()
+                    .map(|inherits| format!(", inherits {}", inherits))
+                    .unwrap_or_default()
+            ))?;
+            for field in class.offsets.iter() {
+                output.emit_line(&format!(
+                    "{} = 0x{:X}  # {}",
+                    field.field_name, field.offset, field.field_ctype
+                ))?;
+            }
+            output.pop_ident();
+        }
+
+        output.pop_ident();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        DumpedSchema,
+        StringEmitter,
+    };
+
+    #[test]
+    fn python_fixture() {
+        let schema =
+            DumpedSchema::from_reader(include_str!("../fixtures/export.json").as_bytes()).unwrap();
+
+        let mut output = StringEmitter::new();
+        super::emit_python(&schema, &mut output).unwrap();
+
+        assert_eq!(
+            output.into_string(),
+            include_str!("../fixtures/export.py.snap").replace("\r\n", "\n")
+        );
+    }
+}
@@ -24,6 +24,8 @@ impl ClassReference {
     }
 }
 
+/// Inheritance of all classes.
+/// Classes are referenced by their Rust name where nested classes (`A::B`) become `A__B`.
 pub struct InheritageMap {
     mapping: BTreeMap<ClassReference, ClassReference>,
 }
@@ -39,7 +41,7 @@ impl InheritageMap {
                 };
 
                 let reference = ClassReference {
-                    class_name: class.class_name.clone(),
+                    class_name: class.class_name.replace(":", "_"),
                     module_name: mod_name_from_schema_name(&scope.schema_name).to_string(),
                 };
 
@@ -74,4 +76,31 @@ impl InheritageMap {
 
         result
     }
+
+    /// All base classes of the given class, starting with the direct base class.
+    pub fn get_inheritance_chain(&self, reference: &ClassReference) -> Vec<ClassReference> {
+        let mut result = Vec::with_capacity(8);
+
+        let mut current = reference;
+        while let Some(inherited_class) = self.mapping.get(current) {
+            if result.contains(inherited_class) {
+                /* class already visited */
+                break;
+            }
+
+            result.push(inherited_class.clone());
+            current = inherited_class;
+        }
+
+        result
+    }
+
+    /// Classes directly inheriting from the given class
+    pub fn get_derived_classes(&self, reference: &ClassReference) -> Vec<ClassReference> {
+        self.mapping
+            .iter()
+            .filter(|(_, inherited_class)| *inherited_class == reference)
+            .map(|(class, _)| class.clone())
+            .collect()
+    }
 }
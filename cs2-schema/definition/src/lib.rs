@@ -1,6 +1,5 @@
 mod definition;
 use std::{
-    collections::BTreeMap,
     fs,
     path::Path,
 };
@@ -18,33 +17,59 @@ mod inheritage;
 pub use inheritage::*;
 
 mod writer;
-use serde::{
-    Deserialize,
-    Serialize,
-};
 pub use writer::*;
 
-#[derive(Debug, Default, Deserialize, Serialize)]
-pub struct DumpedSchema {
-    pub cs2_revision: String,
-    pub cs2_build_datetime: String,
+mod schema_file;
+pub use schema_file::*;
+
+mod diff;
+pub use diff::*;
+
+mod type_mapping;
+pub use type_mapping::*;
+
+mod coverage;
+pub use coverage::*;
+
+mod scope_dependencies;
+pub use scope_dependencies::*;
+
+mod validate;
+pub use validate::*;
 
-    pub resolved_offsets: BTreeMap<String, u64>,
-    pub scopes: Vec<SchemaScope>,
+mod export_c;
+pub use export_c::*;
+
+mod export_csharp;
+pub use export_csharp::*;
+
+mod export_python;
+pub use export_python::*;
+
+/// Lines of Rust code emitted for a schema scope
+pub struct EmittedScope {
+    pub module_name: String,
+    pub lines: usize,
 }
 
-pub fn emit_to_dir(target: impl AsRef<Path>, scopes: &[SchemaScope]) -> anyhow::Result<()> {
+pub fn emit_to_dir(
+    target: impl AsRef<Path>,
+    scopes: &[SchemaScope],
+) -> anyhow::Result<Vec<EmittedScope>> {
     let target = target.as_ref();
     fs::create_dir_all(target).context("mkdirs")?;
 
     let inheritage = InheritageMap::build(scopes);
+    let mut emitted_scopes = Vec::with_capacity(scopes.len());
     for scope in scopes.iter() {
-        let mut writer = FileEmitter::new(target.join(format!(
-            "{}.rs",
-            mod_name_from_schema_name(&scope.schema_name)
-        )))?;
+        let module_name = mod_name_from_schema_name(&scope.schema_name);
+        let mut writer = FileEmitter::new(target.join(format!("{}.rs", module_name)))?;
 
         scope.emit_rust_definition(&mut writer, &inheritage)?;
+        emitted_scopes.push(EmittedScope {
+            module_name: module_name.to_string(),
+            lines: writer.lines_written(),
+        });
     }
 
     /* create the mod.rs */
@@ -54,7 +79,28 @@ pub fn emit_to_dir(target: impl AsRef<Path>, scopes: &[SchemaScope]) -> anyhow::
             let name = mod_name_from_schema_name(&scope.schema_name);
             writer.emit_line(&format!("pub mod {};", name))?;
         }
+
+        /* list of all offsets used by the bindings to allow validating them up front */
+        writer.emit_line("")?;
+        writer.emit_line("pub static RUNTIME_OFFSETS: &[crate::OffsetInfo] = &[")?;
+        writer.push_ident();
+        for scope in scopes.iter() {
+            for class in scope.classes.iter() {
+                for field in class
+                    .offsets
+                    .iter()
+                    .filter(|field| field.field_type.is_some())
+                {
+                    writer.emit_line(&format!(
+                        "crate::OffsetInfo {{ default_value: {}, module: \"{}\", class_name: \"{}\", member: \"{}\" }},",
+                        field.offset, scope.schema_name, class.class_name, field.field_name
+                    ))?;
+                }
+            }
+        }
+        writer.pop_ident();
+        writer.emit_line("];")?;
     }
 
-    Ok(())
+    Ok(emitted_scopes)
 }
@@ -0,0 +1,196 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+        Read,
+        Write,
+    },
+    path::Path,
+};
+
+use anyhow::Context;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::SchemaScope;
+
+/// Current version of the schema file format.
+///
+/// Version history:
+/// - 0: `DumpedSchema` without a format version (initial dumper output)
+/// - 1: `DumpedSchema` with a format version
+///
+/// Files only containing the schema scopes (bare array) are accepted as well.
+pub const SCHEMA_FILE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DumpedSchema {
+    #[serde(default)]
+    pub format_version: u32,
+
+    #[serde(default)]
+    pub cs2_revision: String,
+    #[serde(default)]
+    pub cs2_build_datetime: String,
+
+    #[serde(default)]
+    pub resolved_offsets: BTreeMap<String, u64>,
+    pub scopes: Vec<SchemaScope>,
+}
+
+impl DumpedSchema {
+    /// Parse a schema file.
+    /// Accepts the versioned schema file format as well as the legacy bare scope array.
+    pub fn from_reader(reader: impl Read) -> anyhow::Result<Self> {
+        let value =
+            serde_json::from_reader::<_, serde_json::Value>(reader).context("invalid json")?;
+
+        match value {
+            serde_json::Value::Array(_) => {
+                let scopes = serde_json::from_value::<Vec<SchemaScope>>(value)
+                    .context("parse legacy schema file (scope array)")?;
+
+                Ok(Self {
+                    format_version: 0,
+                    scopes,
+                    ..Default::default()
+                })
+            }
+            serde_json::Value::Object(ref object) => {
+                let format_version = match object.get("format_version") {
+                    Some(version) => {
+                        let version = version
+                            .as_u64()
+                            .context("schema file format version must be an unsigned integer")?;
+
+                        u32::try_from(version).with_context(|| {
+                            format!("schema file format version {} is out of range", version)
+                        })?
+                    }
+                    None => 0,
+                };
+
+                if format_version > SCHEMA_FILE_FORMAT_VERSION {
+                    anyhow::bail!(
+                        "schema file format version {} is not supported (supported up to version {})",
+                        format_version,
+                        SCHEMA_FILE_FORMAT_VERSION
+                    );
+                }
+
+                serde_json::from_value::<Self>(value)
+                    .with_context(|| format!("parse schema file (version {})", format_version))
+            }
+            _ => anyhow::bail!("expected the schema file to contain an object or an array"),
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+        Self::from_reader(BufReader::new(file))
+            .with_context(|| format!("load schema file {}", path.display()))
+    }
+
+    /// Write the schema using the current schema file format version
+    pub fn to_writer(&self, writer: impl Write) -> anyhow::Result<()> {
+        #[derive(Serialize)]
+        struct VersionedSchema<'a> {
+            format_version: u32,
+
+            cs2_revision: &'a str,
+            cs2_build_datetime: &'a str,
+
+            resolved_offsets: &'a BTreeMap<String, u64>,
+            scopes: &'a [SchemaScope],
+        }
+
+        serde_json::to_writer_pretty(
+            writer,
+            &VersionedSchema {
+                format_version: SCHEMA_FILE_FORMAT_VERSION,
+
+                cs2_revision: &self.cs2_revision,
+                cs2_build_datetime: &self.cs2_build_datetime,
+
+                resolved_offsets: &self.resolved_offsets,
+                scopes: &self.scopes,
+            },
+        )?;
+        Ok(())
+    }
+
+    pub fn to_file(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let file = File::options()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(path)
+            .with_context(|| format!("open {}", path.display()))?;
+
+        let mut writer = BufWriter::new(file);
+        self.to_writer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        DumpedSchema,
+        SCHEMA_FILE_FORMAT_VERSION,
+    };
+
+    #[test]
+    fn load_legacy_scope_array() {
+        let schema =
+            DumpedSchema::from_reader(r#"[{ "schema_name": "client.dll" }]"#.as_bytes()).unwrap();
+
+        assert_eq!(schema.format_version, 0);
+        assert_eq!(schema.scopes.len(), 1);
+        assert_eq!(schema.scopes[0].schema_name, "client.dll");
+    }
+
+    #[test]
+    fn reject_unsupported_version() {
+        let error = DumpedSchema::from_reader(
+            format!(
+                r#"{{ "format_version": {}, "scopes": [] }}"#,
+                SCHEMA_FILE_FORMAT_VERSION + 1
+            )
+            .as_bytes(),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("is not supported"));
+
+        /* 2^32 + 1 must not be truncated to version 1 */
+        let error = DumpedSchema::from_reader(
+            r#"{ "format_version": 4294967297, "scopes": [] }"#.as_bytes(),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("out of range"));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let schema =
+            DumpedSchema::from_reader(include_str!("../fixtures/enums.json").as_bytes()).unwrap();
+
+        let mut buffer = Vec::new();
+        schema.to_writer(&mut buffer).unwrap();
+
+        let loaded = DumpedSchema::from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(loaded.format_version, SCHEMA_FILE_FORMAT_VERSION);
+        assert_eq!(loaded.cs2_revision, schema.cs2_revision);
+        assert_eq!(
+            serde_json::to_value(&loaded.scopes).unwrap(),
+            serde_json::to_value(&schema.scopes).unwrap()
+        );
+    }
+}
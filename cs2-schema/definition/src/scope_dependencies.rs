@@ -0,0 +1,164 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+use crate::{
+    mod_name_from_schema_name,
+    SchemaScope,
+};
+
+/// Modules referenced by the emitted Rust definitions of each schema scope.
+/// A module can only be emitted when all the modules it references are emitted as well.
+pub struct ScopeDependencies {
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Module names referenced by a Rust type path (e.g. `EntityHandle<dyn client::C_BaseEntity>`)
+fn referenced_modules(rust_type: &str) -> impl Iterator<Item = &str> {
+    rust_type.match_indices("::").map(|(index, _)| {
+        let prefix = &rust_type[..index];
+        let start = prefix
+            .rfind(|char: char| !char.is_ascii_alphanumeric() && char != '_')
+            .map_or(0, |index| index + 1);
+
+        &prefix[start..]
+    })
+}
+
+impl ScopeDependencies {
+    pub fn build(scopes: &[SchemaScope]) -> Self {
+        let mut dependencies = scopes
+            .iter()
+            .map(|scope| {
+                (
+                    mod_name_from_schema_name(&scope.schema_name).to_string(),
+                    BTreeSet::new(),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        for scope in scopes.iter() {
+            let module_name = mod_name_from_schema_name(&scope.schema_name);
+
+            let mut scope_dependencies = BTreeSet::new();
+            for class in scope.classes.iter() {
+                let field_types = class
+                    .offsets
+                    .iter()
+                    .filter_map(|field| field.field_type.as_deref());
+
+                for rust_type in class.inherits.as_deref().into_iter().chain(field_types) {
+                    for referenced_module in referenced_modules(rust_type) {
+                        if referenced_module == module_name
+                            || !dependencies.contains_key(referenced_module)
+                        {
+                            /* not a schema module */
+                            continue;
+                        }
+
+                        scope_dependencies.insert(referenced_module.to_string());
+                    }
+                }
+            }
+
+            dependencies.insert(module_name.to_string(), scope_dependencies);
+        }
+
+        Self { dependencies }
+    }
+
+    pub fn module_names(&self) -> impl Iterator<Item = &str> {
+        self.dependencies.keys().map(String::as_str)
+    }
+
+    /// Modules directly referenced by the given module
+    pub fn dependencies(&self, module_name: &str) -> impl Iterator<Item = &str> {
+        self.dependencies
+            .get(module_name)
+            .into_iter()
+            .flat_map(|dependencies| dependencies.iter().map(String::as_str))
+    }
+
+    /// The given modules and all modules they (transitively) depend on.
+    pub fn resolve<'a>(&self, modules: impl IntoIterator<Item = &'a str>) -> BTreeSet<String> {
+        let mut result = BTreeSet::new();
+
+        let mut open_list = modules.into_iter().collect::<Vec<_>>();
+        while let Some(module_name) = open_list.pop() {
+            if !result.insert(module_name.to_string()) {
+                /* module already visited */
+                continue;
+            }
+
+            open_list.extend(self.dependencies(module_name));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ScopeDependencies;
+    use crate::{
+        ClassDefinition,
+        ClassField,
+        SchemaScope,
+    };
+
+    fn scope(schema_name: &str, inherits: Option<&str>, field_type: &str) -> SchemaScope {
+        SchemaScope {
+            schema_name: schema_name.to_string(),
+            classes: vec![ClassDefinition {
+                class_name: "Test".to_string(),
+                inherits: inherits.map(ToString::to_string),
+                offsets: vec![ClassField {
+                    field_name: "m_test".to_string(),
+                    field_type: Some(field_type.to_string()),
+                    field_ctype: "test".to_string(),
+                    offset: 0x10,
+                    metadata: vec![],
+                }],
+                ..Default::default()
+            }],
+            enums: vec![],
+        }
+    }
+
+    #[test]
+    fn resolves_transitive_dependencies() {
+        let scopes = [
+            scope("!GlobalTypes", None, "u32"),
+            scope("engine2.dll", Some("globals::Base"), "u32"),
+            scope("client.dll", None, "EntityHandle<dyn engine2::CEntity>"),
+            scope(
+                "server.dll",
+                None,
+                "raw_struct::builtins::Ptr64<dyn server::Test>",
+            ),
+        ];
+
+        let dependencies = ScopeDependencies::build(&scopes);
+        assert_eq!(
+            dependencies.dependencies("client").collect::<Vec<_>>(),
+            ["engine2"]
+        );
+        assert_eq!(dependencies.dependencies("server").count(), 0);
+
+        assert_eq!(
+            dependencies
+                .resolve(["client"])
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["client", "engine2", "globals"]
+        );
+        assert_eq!(
+            dependencies
+                .resolve(["server"])
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["server"]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::borrow::Cow;
+
+/// Wrap dynamic raw_struct types into `Copy<...>` so they can be used as value type
+pub fn wrap_copy_type(rust_type: &str) -> Cow<'_, str> {
+    if rust_type.starts_with("dyn ") {
+        Cow::from(format!("Copy<{}>", rust_type))
+    } else {
+        Cow::from(rust_type)
+    }
+}
+
+/// Schema builtin types and their Rust representation
+const BUILTIN_TYPES: &[(&str, &str)] = &[
+    ("bool", "bool"),
+    ("char", "u8"),
+    ("int8", "i8"),
+    ("uint8", "u8"),
+    ("int16", "i16"),
+    ("uint16", "u16"),
+    ("int32", "i32"),
+    ("uint32", "u32"),
+    ("int64", "i64"),
+    ("uint64", "u64"),
+    ("float32", "f32"),
+    ("float64", "f64"),
+];
+
+pub fn map_builtin_type(builtin_type: &str) -> Option<&'static str> {
+    BUILTIN_TYPES
+        .iter()
+        .find(|(name, _)| *name == builtin_type)
+        .map(|(_, rust_type)| *rust_type)
+}
+
+/// Atomic engine types (without template arguments) which have a fixed Rust representation.
+const ATOMIC_TYPES: &[(&str, &str)] = &[
+    ("CEntityIndex", "CEntityIndex"),
+    ("CEntityHandle", "EntityHandle<()>"),
+    ("CUtlStringToken", "dyn CUtlStringToken"),
+    (
+        "CUtlStringTokenWithStorage",
+        "dyn CUtlStringTokenWithStorage",
+    ),
+    ("CUtlSymbolLarge", "PtrCStr"),
+    ("CGlobalSymbol", "PtrCStr"),
+    ("CUtlString", "dyn CUtlString"),
+    ("Color", "Color"),
+    ("CNetworkedQuantizedFloat", "f32"),
+    ("GameTime_t", "f32"),
+    ("GameTick_t", "i32"),
+    ("Vector", "[f32; 0x03]"),
+    ("QAngle", "[f32; 0x04]"),
+    ("Vector2D", "Vector2D"),
+    ("Vector4D", "Vector4D"),
+    ("VectorAligned", "VectorAligned"),
+    ("Quaternion", "Quaternion"),
+    ("QuaternionStorage", "QuaternionStorage"),
+    ("RotationVector", "RotationVector"),
+    ("RadianEuler", "RadianEuler"),
+    ("DegreeEuler", "DegreeEuler"),
+    ("matrix3x4_t", "Matrix3x4"),
+    ("matrix3x4a_t", "Matrix3x4"),
+    ("CTransform", "dyn CTransform"),
+];
+
+pub fn map_atomic_type(atomic_type: &str) -> Option<&'static str> {
+    ATOMIC_TYPES
+        .iter()
+        .find(|(name, _)| *name == atomic_type)
+        .map(|(_, rust_type)| *rust_type)
+}
+
+/// Template types where the template argument does not change the memory layout.
+const RESOURCE_HANDLE_TYPES: &[&str] = &["CStrongHandle", "CStrongHandleCopyable", "CWeakHandle"];
+
+/// Map an engine template type (e.g. `CHandle< C_BaseEntity >`) onto the Rust type.
+/// `inner_type` is the mapped Rust type of the template argument (if it could be mapped).
+pub fn map_template_type(template_ctype: &str, inner_type: Option<&str>) -> Option<String> {
+    let (template_name, _) = template_ctype.split_once('<')?;
+    match template_name.trim() {
+        "CHandle" => inner_type.map(|inner_type| format!("EntityHandle<{}>", inner_type)),
+        name if RESOURCE_HANDLE_TYPES.contains(&name) => Some("CResourceHandle".to_string()),
+        _ => None,
+    }
+}
+
+/// Map a bitfield (e.g. `bitfield:3`) onto the smallest unsigned integer holding all bits.
+/// The bits of the value are not shifted nor masked, which must be done by the caller.
+pub fn map_bitfield_type(bitfield_ctype: &str) -> Option<&'static str> {
+    let bits = bitfield_ctype
+        .strip_prefix("bitfield:")?
+        .trim()
+        .parse::<u32>()
+        .ok()?;
+
+    match bits {
+        1..=8 => Some("u8"),
+        9..=16 => Some("u16"),
+        17..=32 => Some("u32"),
+        33..=64 => Some("u64"),
+        _ => None,
+    }
+}
+
+/// Collections which can be accessed through the given `cs2_schema_cutl` type.
+///
+/// All listed collections start with the element count followed by the element pointer
+/// at offset 0x08. Collections with inline storage (e.g. `CUtlLeanVectorFixedGrowable`)
+/// are not listed as the element pointer is only valid once the collection grew.
+const COLLECTION_TYPES: &[(&str, &str)] = &[
+    ("CUtlVector", "CUtlVector"),
+    ("CUtlVectorFixedGrowable", "CUtlVector"),
+    ("C_NetworkUtlVectorBase", "CUtlVector"),
+    ("CNetworkUtlVectorBase", "CUtlVector"),
+    ("C_UtlVectorEmbeddedNetworkVar", "CUtlVector"),
+    ("CUtlVectorEmbeddedNetworkVar", "CUtlVector"),
+    ("CUtlLeanVector", "CUtlLeanVector"),
+];
+
+/// Map an engine collection type (e.g. `CUtlVector< int32 >`) onto the Rust type.
+/// `inner_type` must be the already mapped Rust type of the collection elements.
+pub fn map_collection_type(collection_ctype: &str, inner_type: &str) -> Option<String> {
+    let (collection_name, _) = collection_ctype.split_once('<')?;
+    let collection_name = collection_name.trim();
+
+    let (_, rust_type) = COLLECTION_TYPES
+        .iter()
+        .find(|(name, _)| *name == collection_name)?;
+
+    Some(format!("dyn {}<{}>", rust_type, wrap_copy_type(inner_type)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        map_atomic_type,
+        map_bitfield_type,
+        map_builtin_type,
+        map_collection_type,
+        map_template_type,
+    };
+    use crate::{
+        DumpedSchema,
+        InheritageMap,
+        StringEmitter,
+    };
+
+    #[test]
+    fn collection_types() {
+        assert_eq!(
+            map_collection_type("CUtlVector< int32 >", "i32").as_deref(),
+            Some("dyn CUtlVector<i32>")
+        );
+        assert_eq!(
+            map_collection_type(
+                "C_NetworkUtlVectorBase< CHandle< C_BaseEntity > >",
+                "EntityHandle<dyn client::C_BaseEntity>"
+            )
+            .as_deref(),
+            Some("dyn CUtlVector<EntityHandle<dyn client::C_BaseEntity>>")
+        );
+        assert_eq!(
+            map_collection_type(
+                "C_UtlVectorEmbeddedNetworkVar< EntityRenderAttribute_t >",
+                "dyn client::EntityRenderAttribute_t"
+            )
+            .as_deref(),
+            Some("dyn CUtlVector<Copy<dyn client::EntityRenderAttribute_t>>")
+        );
+        assert_eq!(
+            map_collection_type("CUtlVectorFixedGrowable< int16, 5 >", "i16").as_deref(),
+            Some("dyn CUtlVector<i16>")
+        );
+        assert_eq!(
+            map_collection_type("CUtlLeanVector< float32 >", "f32").as_deref(),
+            Some("dyn CUtlLeanVector<f32>")
+        );
+
+        assert_eq!(
+            map_collection_type("CUtlLeanVectorFixedGrowable< int32, 1 >", "i32"),
+            None
+        );
+        assert_eq!(map_collection_type("CUtlHashtable< int32 >", "i32"), None);
+        assert_eq!(map_collection_type("int32", "i32"), None);
+    }
+
+    #[test]
+    fn atomic_types() {
+        assert_eq!(map_builtin_type("float32"), Some("f32"));
+        assert_eq!(map_builtin_type("float128"), None);
+
+        assert_eq!(map_atomic_type("Vector2D"), Some("Vector2D"));
+        assert_eq!(map_atomic_type("matrix3x4_t"), Some("Matrix3x4"));
+        assert_eq!(map_atomic_type("CTransform"), Some("dyn CTransform"));
+        assert_eq!(map_atomic_type("fltx4"), None);
+
+        assert_eq!(
+            map_template_type("CHandle< C_BaseEntity >", Some("dyn client::C_BaseEntity"))
+                .as_deref(),
+            Some("EntityHandle<dyn client::C_BaseEntity>")
+        );
+        assert_eq!(map_template_type("CHandle< C_BaseEntity >", None), None);
+        assert_eq!(
+            map_template_type("CStrongHandle< InfoForResourceTypeCModel >", None).as_deref(),
+            Some("CResourceHandle")
+        );
+        assert_eq!(map_template_type("CSmartPtr< CAnimGraph >", None), None);
+
+        assert_eq!(map_bitfield_type("bitfield:1"), Some("u8"));
+        assert_eq!(map_bitfield_type("bitfield:9"), Some("u16"));
+        assert_eq!(map_bitfield_type("bitfield:65"), None);
+        assert_eq!(map_bitfield_type("int32"), None);
+    }
+
+    #[test]
+    fn collection_fields_fixture() {
+        let schema =
+            DumpedSchema::from_reader(include_str!("../fixtures/collections.json").as_bytes())
+                .unwrap();
+
+        let inheritage = InheritageMap::build(&schema.scopes);
+        let mut output = StringEmitter::new();
+        for scope in schema.scopes.iter() {
+            scope
+                .emit_rust_definition(&mut output, &inheritage)
+                .unwrap();
+        }
+
+        assert_eq!(
+            output.into_string(),
+            include_str!("../fixtures/collections.rs.snap").replace("\r\n", "\n")
+        );
+    }
+}
@@ -0,0 +1,449 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fmt::{
+        self,
+        Display,
+    },
+};
+
+use crate::{
+    mod_name_from_schema_name,
+    ClassDefinition,
+    ClassField,
+    ClassReference,
+    DumpedSchema,
+};
+
+/// An inconsistency found within a schema file
+#[derive(Debug)]
+pub struct SchemaIssue {
+    pub schema_name: String,
+
+    /// Name of the affected class or enum
+    pub type_name: String,
+
+    pub message: String,
+}
+
+impl Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}::{}: {}",
+            mod_name_from_schema_name(&self.schema_name),
+            self.type_name,
+            self.message
+        )
+    }
+}
+
+/// Size of types which do not depend on the schema itself
+const FIXED_TYPE_SIZES: &[(&str, u64)] = &[
+    ("bool", 1),
+    ("u8", 1),
+    ("i8", 1),
+    ("u16", 2),
+    ("i16", 2),
+    ("u32", 4),
+    ("i32", 4),
+    ("f32", 4),
+    ("u64", 8),
+    ("i64", 8),
+    ("f64", 8),
+    ("PtrCStr", 8),
+    ("CEntityIndex", 4),
+    ("Color", 4),
+    ("CResourceHandle", 8),
+    ("Vector2D", 0x08),
+    ("Vector4D", 0x10),
+    ("VectorAligned", 0x10),
+    ("Quaternion", 0x10),
+    ("QuaternionStorage", 0x10),
+    ("RotationVector", 0x0C),
+    ("RadianEuler", 0x0C),
+    ("DegreeEuler", 0x0C),
+    ("Matrix3x4", 0x30),
+    ("dyn CTransform", 0x20),
+    ("dyn CUtlStringToken", 0x04),
+];
+
+/// Engine types whose mapped Rust type differs in size from the engine type
+/// (e.g. `QAngle` is mapped to four floats but only consists of three).
+const ENGINE_TYPE_SIZES: &[(&str, u64)] = &[("QAngle", 0x0C)];
+
+fn parse_array_length(length: &str) -> Option<u64> {
+    let length = length.trim();
+    match length.strip_prefix("0x") {
+        Some(length) => u64::from_str_radix(length, 16).ok(),
+        None => length.parse().ok(),
+    }
+}
+
+/// Split a fixed array type (e.g. `[f32; 0x03]`) into its element type and length
+fn parse_array_type(rust_type: &str) -> Option<(&str, Option<u64>)> {
+    let (element_type, length) = rust_type
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .rsplit_once(';')?;
+
+    Some((element_type.trim(), parse_array_length(length)))
+}
+
+/// Size of an engine type (e.g. `QAngle[2]`) listed in `ENGINE_TYPE_SIZES`
+fn engine_type_size(ctype: &str) -> Option<u64> {
+    let (base_type, dimensions) = match ctype.split_once('[') {
+        Some((base_type, dimensions)) => (base_type, Some(dimensions)),
+        None => (ctype, None),
+    };
+
+    let (_, mut size) = ENGINE_TYPE_SIZES
+        .iter()
+        .find(|(name, _)| *name == base_type.trim())?;
+
+    for dimension in dimensions
+        .into_iter()
+        .flat_map(|dimensions| dimensions.split('['))
+    {
+        size *= parse_array_length(dimension.trim_end_matches(']'))?;
+    }
+
+    Some(size)
+}
+
+fn is_bitfield(field: &ClassField) -> bool {
+    field.field_ctype.starts_with("bitfield:")
+}
+
+/// Classes are referenced by their Rust name where nested classes (`A::B`) become `A__B`
+fn class_reference(module_name: &str, class_name: &str) -> ClassReference {
+    ClassReference {
+        module_name: module_name.to_string(),
+        class_name: class_name.replace(":", "_"),
+    }
+}
+
+struct SchemaValidator<'a> {
+    classes: BTreeMap<ClassReference, &'a ClassDefinition>,
+    enum_sizes: BTreeMap<(&'a str, &'a str), u64>,
+
+    issues: Vec<SchemaIssue>,
+}
+
+impl<'a> SchemaValidator<'a> {
+    fn new(schema: &'a DumpedSchema) -> Self {
+        let mut classes = BTreeMap::new();
+        let mut enum_sizes = BTreeMap::new();
+        for scope in schema.scopes.iter() {
+            let module_name = mod_name_from_schema_name(&scope.schema_name);
+            for class in scope.classes.iter() {
+                /* duplicated classes are reported during validation */
+                classes
+                    .entry(class_reference(module_name, &class.class_name))
+                    .or_insert(class);
+            }
+
+            for definition in scope.enums.iter() {
+                enum_sizes.insert(
+                    (module_name, definition.enum_name.as_str()),
+                    definition.enum_size as u64,
+                );
+            }
+        }
+
+        Self {
+            classes,
+            enum_sizes,
+
+            issues: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, schema_name: &str, type_name: &str, message: String) {
+        self.issues.push(SchemaIssue {
+            schema_name: schema_name.to_string(),
+            type_name: type_name.to_string(),
+            message,
+        });
+    }
+
+    /// Size of the Rust type in bytes, if known
+    fn type_size(&self, rust_type: &str) -> Option<u64> {
+        let rust_type = rust_type.trim();
+        if let Some((_, size)) = FIXED_TYPE_SIZES.iter().find(|(name, _)| *name == rust_type) {
+            return Some(*size);
+        }
+
+        if rust_type.starts_with("Ptr64<") {
+            return Some(8);
+        }
+
+        if rust_type.starts_with("EntityHandle<") {
+            return Some(4);
+        }
+
+        if let Some(inner_type) = rust_type
+            .strip_prefix("Copy<")
+            .and_then(|value| value.strip_suffix('>'))
+        {
+            return self.type_size(inner_type);
+        }
+
+        if let Some((element_type, length)) = parse_array_type(rust_type) {
+            return Some(self.type_size(element_type)? * length?);
+        }
+
+        let (module_name, type_name) = rust_type
+            .strip_prefix("dyn ")
+            .unwrap_or(rust_type)
+            .split_once("::")?;
+
+        if let Some(size) = self.enum_sizes.get(&(module_name, type_name)) {
+            return Some(*size);
+        }
+
+        self.classes
+            .get(&class_reference(module_name, type_name))
+            .map(|class| class.class_size)
+    }
+
+    fn field_size(&self, field: &ClassField) -> Option<u64> {
+        if let Some(size) = engine_type_size(&field.field_ctype) {
+            return Some(size);
+        }
+
+        self.type_size(field.field_type.as_deref()?)
+    }
+
+    fn validate_fields(&mut self, schema_name: &str, class: &ClassDefinition) {
+        /* bitfields do not have a meaningful offset */
+        let mut fields = class
+            .offsets
+            .iter()
+            .filter(|field| !is_bitfield(field))
+            .collect::<Vec<_>>();
+        fields.sort_by_key(|field| field.offset);
+
+        let mut previous_field: Option<(&ClassField, u64)> = None;
+        for field in fields {
+            let field_size = self.field_size(field);
+
+            if let Some((element_type, length)) =
+                field.field_type.as_deref().and_then(parse_array_type)
+            {
+                let element_size = self.type_size(element_type).unwrap_or(1);
+                let array_size = length.map(|length| length.saturating_mul(element_size));
+                if array_size.is_none_or(|array_size| array_size > class.class_size) {
+                    self.report(
+                        schema_name,
+                        &class.class_name,
+                        format!(
+                            "fixed array {} ({}) is larger than the class size of 0x{:X}",
+                            field.field_name,
+                            field.field_type.as_deref().unwrap_or_default(),
+                            class.class_size
+                        ),
+                    );
+                }
+            }
+
+            if field.offset + field_size.unwrap_or(1) > class.class_size {
+                self.report(
+                    schema_name,
+                    &class.class_name,
+                    format!(
+                        "field {} at 0x{:X} (size {}) exceeds the class size of 0x{:X}",
+                        field.field_name,
+                        field.offset,
+                        field_size.map_or("unknown".to_string(), |size| format!("0x{:X}", size)),
+                        class.class_size
+                    ),
+                );
+            }
+
+            if let Some((previous_field, previous_size)) = previous_field {
+                if previous_field.offset + previous_size > field.offset {
+                    self.report(
+                        schema_name,
+                        &class.class_name,
+                        format!(
+                            "field {} at 0x{:X} (size 0x{:X}) overlaps with field {} at 0x{:X}",
+                            previous_field.field_name,
+                            previous_field.offset,
+                            previous_size,
+                            field.field_name,
+                            field.offset
+                        ),
+                    );
+                }
+            }
+
+            if let Some(field_size) = field_size {
+                let field_end = field.offset + field_size;
+                if previous_field.is_none_or(|(previous_field, previous_size)| {
+                    previous_field.offset + previous_size < field_end
+                }) {
+                    previous_field = Some((field, field_size));
+                }
+            }
+        }
+    }
+
+    fn validate_inheritance(&mut self, schema_name: &str, class: &ClassDefinition) {
+        let Some(inherits) = &class.inherits else {
+            return;
+        };
+
+        let Some(mut base_reference) = ClassReference::from_rs_path(inherits) else {
+            self.report(
+                schema_name,
+                &class.class_name,
+                format!("invalid base class reference {}", inherits),
+            );
+            return;
+        };
+
+        let Some(base_class) = self.classes.get(&base_reference).copied() else {
+            self.report(
+                schema_name,
+                &class.class_name,
+                format!("base class {} does not exist", inherits),
+            );
+            return;
+        };
+
+        if base_class.class_size > class.class_size {
+            self.report(
+                schema_name,
+                &class.class_name,
+                format!(
+                    "class size 0x{:X} is smaller than the size 0x{:X} of the base class {}",
+                    class.class_size, base_class.class_size, inherits
+                ),
+            );
+        }
+
+        /* fields of the base classes must not be shadowed by fields of the derived class */
+        let mut visited = BTreeSet::new();
+        let mut base_class = base_class;
+        loop {
+            for field in class.offsets.iter().filter(|field| !is_bitfield(field)) {
+                let field_end = field.offset + self.field_size(field).unwrap_or(1);
+                for base_field in base_class
+                    .offsets
+                    .iter()
+                    .filter(|field| !is_bitfield(field))
+                {
+                    let base_field_end =
+                        base_field.offset + self.field_size(base_field).unwrap_or(1);
+                    if field.offset >= base_field_end || base_field.offset >= field_end {
+                        continue;
+                    }
+
+                    self.report(
+                        schema_name,
+                        &class.class_name,
+                        format!(
+                            "field {} at 0x{:X} conflicts with field {}::{}.{} at 0x{:X}",
+                            field.field_name,
+                            field.offset,
+                            base_reference.module_name,
+                            base_reference.class_name,
+                            base_field.field_name,
+                            base_field.offset
+                        ),
+                    );
+                }
+            }
+
+            if !visited.insert(base_reference.clone()) {
+                /* class already visited */
+                break;
+            }
+
+            let Some(next_reference) = base_class
+                .inherits
+                .as_deref()
+                .and_then(ClassReference::from_rs_path)
+            else {
+                break;
+            };
+            let Some(next_class) = self.classes.get(&next_reference).copied() else {
+                /* reported by the validation of the base class itself */
+                break;
+            };
+
+            base_reference = next_reference;
+            base_class = next_class;
+        }
+    }
+
+    fn validate(mut self, schema: &DumpedSchema) -> Vec<SchemaIssue> {
+        for scope in schema.scopes.iter() {
+            let mut class_names = BTreeSet::new();
+            for class in scope.classes.iter() {
+                if !class_names.insert(class.class_name.as_str()) {
+                    self.report(
+                        &scope.schema_name,
+                        &class.class_name,
+                        "duplicate class name".to_string(),
+                    );
+                }
+
+                self.validate_fields(&scope.schema_name, class);
+                self.validate_inheritance(&scope.schema_name, class);
+            }
+
+            for definition in scope.enums.iter() {
+                if ![1, 2, 4, 8].contains(&definition.enum_size) {
+                    self.report(
+                        &scope.schema_name,
+                        &definition.enum_name,
+                        format!("invalid enum size {}", definition.enum_size),
+                    );
+                }
+            }
+        }
+
+        self.issues
+    }
+}
+
+/// Validate the schema for inconsistencies like overlapping fields
+/// or fields exceeding their class.
+pub fn validate_schema(schema: &DumpedSchema) -> Vec<SchemaIssue> {
+    SchemaValidator::new(schema).validate(schema)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::DumpedSchema;
+
+    #[test]
+    fn validate_fixture() {
+        let schema =
+            DumpedSchema::from_reader(include_str!("../fixtures/validate.json").as_bytes())
+                .unwrap();
+
+        let issues = super::validate_schema(&schema)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            issues,
+            [
+                "globals::CBase: duplicate class name",
+                "globals::EBroken: invalid enum size 3",
+                "client::CDerived: field m_vecPosition at 0x20 (size 0xC) overlaps with field m_flOverlapping at 0x28",
+                "client::CDerived: fixed array m_aValues ([u32; 0xFFFF]) is larger than the class size of 0x40",
+                "client::CDerived: field m_aValues at 0x38 (size 0x3FFFC) exceeds the class size of 0x40",
+                "client::CDerived: field m_nShadow at 0x14 conflicts with field globals::CBase.m_nBase at 0x10",
+                "client::CDangling: field m_hOwner at 0x6 (size 0x4) exceeds the class size of 0x8",
+                "client::CDangling: base class client::CMissing does not exist",
+            ]
+        );
+    }
+}
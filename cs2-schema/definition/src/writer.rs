@@ -19,6 +19,7 @@ pub trait EmitOutput {
 pub struct FileEmitter {
     writer: BufWriter<File>,
     ident_stack: Vec<String>,
+    lines_written: usize,
 }
 
 impl FileEmitter {
@@ -31,9 +32,14 @@ impl FileEmitter {
         Ok(Self {
             writer: BufWriter::new(file),
             ident_stack: Vec::with_capacity(8),
+            lines_written: 0,
         })
     }
 
+    pub fn lines_written(&self) -> usize {
+        self.lines_written
+    }
+
     fn current_ident(&self) -> &str {
         self.ident_stack.last().map_or("", |v| v.as_str())
     }
@@ -41,6 +47,11 @@ impl FileEmitter {
 
 impl EmitOutput for FileEmitter {
     fn emit_line(&mut self, line: &str) -> io::Result<()> {
+        self.lines_written += 1;
+        if line.is_empty() {
+            return writeln!(&mut self.writer);
+        }
+
         let ident = self.ident_stack.last().map_or("".into(), Cow::from);
         writeln!(&mut self.writer, "{}{}", ident, line)
     }
@@ -54,3 +65,42 @@ impl EmitOutput for FileEmitter {
         let _ = self.ident_stack.pop();
     }
 }
+
+/// Emits everything into an in memory string.
+/// Mainly used to verify the emitted output.
+#[derive(Default)]
+pub struct StringEmitter {
+    output: String,
+    ident_stack: Vec<String>,
+}
+
+impl StringEmitter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn into_string(self) -> String {
+        self.output
+    }
+}
+
+impl EmitOutput for StringEmitter {
+    fn emit_line(&mut self, line: &str) -> io::Result<()> {
+        if let Some(ident) = self.ident_stack.last().filter(|_| !line.is_empty()) {
+            self.output.push_str(ident);
+        }
+
+        self.output.push_str(line);
+        self.output.push('\n');
+        Ok(())
+    }
+
+    fn push_ident(&mut self) {
+        let ident = format!("{}    ", self.ident_stack.last().map_or("", |v| v.as_str()));
+        self.ident_stack.push(ident);
+    }
+
+    fn pop_ident(&mut self) {
+        let _ = self.ident_stack.pop();
+    }
+}
@@ -1,7 +1,5 @@
 use std::{
     collections::BTreeMap,
-    fs::File,
-    io::BufWriter,
     path::{
         self,
         PathBuf,
@@ -9,7 +7,10 @@ use std::{
 };
 
 use anyhow::Context;
-use clap::Parser;
+use clap::{
+    Parser,
+    ValueEnum,
+};
 use cs2::{
     CS2Handle,
     CS2Offset,
@@ -19,7 +20,11 @@ use cs2::{
     StateCS2Memory,
     StateResolvedOffset,
 };
-use cs2_schema_definition::DumpedSchema;
+use cs2_schema_definition::{
+    DumpedSchema,
+    FileEmitter,
+    SchemaCoverage,
+};
 use log::LevelFilter;
 use utils_state::StateRegistry;
 
@@ -34,6 +39,31 @@ struct Args {
     /// to generate the schema definitions but should be enough for providing runtime offsets.
     #[clap(long, short)]
     pub client_only: bool,
+
+    /// Output format of the target file.
+    /// Only the schema file (json) can be used to generate the Rust bindings.
+    #[clap(long, short, value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+
+    /// Write the schema even if the schema validation reports issues
+    /// (e.g. overlapping fields or fields exceeding their class).
+    #[clap(long)]
+    pub skip_validation: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Schema file
+    Json,
+
+    /// C/C++ header with offset constants
+    C,
+
+    /// C# static classes with offset constants
+    Csharp,
+
+    /// Python module with offset constants
+    Python,
 }
 
 fn dump_offsets(states: &StateRegistry) -> anyhow::Result<BTreeMap<String, u64>> {
@@ -96,14 +126,33 @@ fn main() -> anyhow::Result<()> {
         schema.cs2_revision = build_info.revision.clone();
     }
 
-    let output = File::options()
-        .create(true)
-        .truncate(true)
-        .write(true)
-        .open(&args.target_file)?;
+    let issues = cs2_schema_definition::validate_schema(&schema);
+    if !issues.is_empty() {
+        for issue in issues.iter() {
+            log::warn!("{}", issue);
+        }
 
-    let mut output = BufWriter::new(output);
-    serde_json::to_writer_pretty(&mut output, &schema)?;
+        if !args.skip_validation {
+            anyhow::bail!(
+                "schema validation reported {} issues (use --skip-validation to write the schema anyways)",
+                issues.len()
+            );
+        }
+    }
+
+    match args.format {
+        OutputFormat::Json => schema.to_file(&args.target_file)?,
+        OutputFormat::C => cs2_schema_definition::emit_c_header(
+            &schema,
+            &mut FileEmitter::new(&args.target_file)?,
+        )?,
+        OutputFormat::Csharp => {
+            cs2_schema_definition::emit_csharp(&schema, &mut FileEmitter::new(&args.target_file)?)?
+        }
+        OutputFormat::Python => {
+            cs2_schema_definition::emit_python(&schema, &mut FileEmitter::new(&args.target_file)?)?
+        }
+    }
 
     let absolute_path = path::absolute(&args.target_file).unwrap_or(args.target_file.clone());
     log::info!(
@@ -112,5 +161,13 @@ fn main() -> anyhow::Result<()> {
         schema.cs2_build_datetime,
         absolute_path.display()
     );
+
+    let coverage = SchemaCoverage::compute(&schema);
+    log::info!(
+        "{} of {} fields typed ({:.1}%)",
+        coverage.typed_fields,
+        coverage.total_fields(),
+        coverage.coverage()
+    );
     Ok(())
 }
@@ -1,24 +1,133 @@
 use std::{
+    collections::BTreeSet,
     env,
-    fs::File,
-    io::BufReader,
+    fmt::Write,
+    fs,
     path::PathBuf,
     str::FromStr,
 };
 
 use anyhow::Context;
-use cs2_schema_definition::SchemaScope;
+use cs2_schema_definition::{
+    mod_name_from_schema_name,
+    DumpedSchema,
+    ScopeDependencies,
+};
+
+/// Every schema scope is gated behind the cargo feature named like its module
+/// (e.g. `client.dll` -> `client`). Enabling `all-scopes` emits every scope.
+fn is_feature_enabled(feature: &str) -> bool {
+    let feature = feature.to_uppercase().replace('-', "_");
+    env::var_os(format!("CARGO_FEATURE_{}", feature)).is_some()
+}
+
+/// Names of all features declared in the `[features]` table of our Cargo.toml
+fn manifest_features() -> anyhow::Result<BTreeSet<String>> {
+    let manifest_path = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?).join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("read {}", manifest_path.display()))?;
+
+    let mut features = BTreeSet::new();
+    let mut in_features = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_features = line == "[features]";
+            continue;
+        }
+
+        if !in_features || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((name, _)) = line.split_once('=') {
+            features.insert(name.trim().to_string());
+        }
+    }
+
+    Ok(features)
+}
 
 fn main() -> anyhow::Result<()> {
-    let mut schema =
-        BufReader::new(File::open("./cs2_schema.json").context("failed to open cs2_schema.json")?);
-    let schema_scopes = serde_json::from_reader::<_, Vec<SchemaScope>>(&mut schema)
-        .context("failed to parse schema")?;
+    let schema =
+        DumpedSchema::from_file("./cs2_schema.json").context("failed to load cs2_schema.json")?;
+
+    let issues = cs2_schema_definition::validate_schema(&schema);
+    if !issues.is_empty() {
+        for issue in issues.iter() {
+            println!("cargo:warning={}", issue);
+        }
+
+        anyhow::bail!("cs2_schema.json is invalid ({} issues)", issues.len());
+    }
+
+    let dependencies = ScopeDependencies::build(&schema.scopes);
+
+    /* a scope without a feature could only be emitted with `all-scopes` */
+    let features = manifest_features()?;
+    let missing_features = dependencies
+        .module_names()
+        .filter(|module_name| !features.contains(*module_name))
+        .collect::<Vec<_>>();
+    if !missing_features.is_empty() {
+        for module_name in missing_features.iter() {
+            println!(
+                "cargo:warning=schema scope {} has no feature. Add `{} = []` to the features of Cargo.toml.",
+                module_name, module_name
+            );
+        }
+
+        anyhow::bail!(
+            "missing features for the schema scopes {}",
+            missing_features.join(", ")
+        );
+    }
+
+    let all_scopes = is_feature_enabled("all-scopes");
+    let requested_modules = dependencies
+        .module_names()
+        .filter(|module_name| all_scopes || is_feature_enabled(module_name))
+        .collect::<BTreeSet<_>>();
+
+    /* modules referenced by the requested modules must be emitted as well */
+    let enabled_modules = dependencies.resolve(requested_modules.iter().copied());
+    let scopes = schema
+        .scopes
+        .into_iter()
+        .filter(|scope| enabled_modules.contains(mod_name_from_schema_name(&scope.schema_name)))
+        .collect::<Vec<_>>();
 
     let dest_path = PathBuf::from_str(&env::var("OUT_DIR")?)?;
-    cs2_schema_definition::emit_to_dir(&dest_path, &schema_scopes)?;
+    let emitted_scopes = cs2_schema_definition::emit_to_dir(&dest_path, &scopes)?;
+
+    let mut report = String::new();
+    writeln!(&mut report, "Generated lines per scope:")?;
+    for scope in emitted_scopes.iter() {
+        writeln!(
+            &mut report,
+            "  {:<24} {:>8}{}",
+            scope.module_name,
+            scope.lines,
+            if requested_modules.contains(scope.module_name.as_str()) {
+                ""
+            } else {
+                " (dependency)"
+            }
+        )?;
+    }
+    writeln!(
+        &mut report,
+        "  {:<24} {:>8}",
+        "total",
+        emitted_scopes
+            .iter()
+            .map(|scope| scope.lines)
+            .sum::<usize>()
+    )?;
+    fs::write(dest_path.join("report.txt"), &report).context("write report.txt")?;
 
+    print!("{}", report);
     println!("Target dir: {}", dest_path.display());
     println!("cargo:rerun-if-changed=cs2_schema.json");
+    println!("cargo:rerun-if-changed=Cargo.toml");
     Ok(())
 }
@@ -2,9 +2,7 @@
 #![feature(sync_unsafe_cell)]
 
 pub(crate) use cs2_schema_provider::runtime_offset;
-
-// FIXME: Correct type here. Is it a 3xf32, 4xf32 or 3xu8 or 4xu8
-pub type Color = u8;
+pub use cs2_schema_provider::OffsetInfo;
 
 pub mod cs2 {
     #![allow(
@@ -1,4 +1,6 @@
 mod provider;
+mod validate;
 
 pub use cs2_schema_provider::*;
 pub use provider::*;
+pub use validate::*;
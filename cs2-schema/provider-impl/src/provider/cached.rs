@@ -30,6 +30,10 @@ impl CachedSchemaProvider {
     pub fn new(offsets: BTreeMap<CachedOffset, u64>) -> Self {
         Self { offsets }
     }
+
+    pub fn offsets(&self) -> &BTreeMap<CachedOffset, u64> {
+        &self.offsets
+    }
 }
 
 impl SchemaProvider for CachedSchemaProvider {
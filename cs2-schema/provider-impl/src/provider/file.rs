@@ -1,7 +1,5 @@
 use std::{
     collections::BTreeMap,
-    fs::File,
-    io::BufReader,
     path::Path,
 };
 
@@ -59,15 +57,23 @@ impl SchemaProvider for FileSchemaProvider {
 }
 
 pub fn setup_schema_from_file(states: &mut StateRegistry, file: &Path) -> anyhow::Result<()> {
-    let file = File::open(file).context("open file")?;
-    let reader = BufReader::new(file);
-    let schema = serde_json::from_reader::<_, DumpedSchema>(reader).context("parse schema file")?;
+    let provider = load_schema_file(states, file)?;
+    cs2_schema_provider::setup_provider(Box::new(provider));
+    Ok(())
+}
 
-    {
-        let provider = FileSchemaProvider::new(&schema.scopes)?;
-        cs2_schema_provider::setup_provider(Box::new(provider));
+/// Load the schema file and register the module offsets resolved within the file.
+pub fn load_schema_file(
+    states: &mut StateRegistry,
+    file: &Path,
+) -> anyhow::Result<FileSchemaProvider> {
+    let schema = DumpedSchema::from_file(file)?;
+    if schema.resolved_offsets.is_empty() {
+        log::info!("Schema file does not contain any resolved module offsets. Offsets will be resolved at runtime.");
     }
 
+    let provider = FileSchemaProvider::new(&schema.scopes)?;
+
     for offset in CS2Offset::available_offsets() {
         if let Some(value) = schema.resolved_offsets.get(offset.cache_name()).cloned() {
             let predefined_offset = StatePredefinedOffset::new(states, *offset, value)
@@ -83,5 +89,5 @@ pub fn setup_schema_from_file(states: &mut StateRegistry, file: &Path) -> anyhow
         }
     }
 
-    Ok(())
+    Ok(provider)
 }
@@ -0,0 +1,365 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{
+        self,
+        Display,
+    },
+    path::Path,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use cs2_schema_provider::{
+    OffsetInfo,
+    SchemaProvider,
+};
+use utils_state::StateRegistry;
+
+use super::{
+    load_schema_file,
+    CachedOffset,
+    OffsetCache,
+    RuntimeSchemaProvider,
+};
+
+/// Resolution result of a single offset
+#[derive(Debug, Clone)]
+pub struct OffsetResolution {
+    /// Name of the layer which supplied the offset
+    pub layer: &'static str,
+    pub value: u64,
+
+    /// Lower priority layers which know a different value for this offset
+    pub disagreements: Vec<(&'static str, u64)>,
+}
+
+/// Schema provider trying multiple providers in order.
+/// The first provider (layer) knowing an offset supplies it.
+///
+/// Every resolution is recorded including the layer which supplied
+/// the offset and layers which disagree with the supplied value.
+#[derive(Default)]
+pub struct LayeredSchemaProvider {
+    layers: Vec<(&'static str, Box<dyn SchemaProvider>)>,
+    resolutions: Mutex<BTreeMap<CachedOffset, OffsetResolution>>,
+}
+
+impl LayeredSchemaProvider {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a layer with a lower priority than all previously added layers
+    pub fn with_layer(mut self, name: &'static str, provider: Box<dyn SchemaProvider>) -> Self {
+        self.layers.push((name, provider));
+        self
+    }
+
+    pub fn layer_names(&self) -> Vec<&'static str> {
+        self.layers.iter().map(|(name, _)| *name).collect()
+    }
+
+    pub fn diagnostics(&self) -> SchemaDiagnostics {
+        SchemaDiagnostics {
+            layers: self.layer_names(),
+            resolutions: self.resolutions.lock().unwrap().clone(),
+        }
+    }
+}
+
+impl SchemaProvider for LayeredSchemaProvider {
+    fn resolve_offset(&self, offset: &OffsetInfo) -> Option<u64> {
+        let mut resolution: Option<OffsetResolution> = None;
+        for (layer, provider) in self.layers.iter() {
+            let Some(value) = provider.resolve_offset(offset) else {
+                continue;
+            };
+
+            match &mut resolution {
+                Some(resolution) if resolution.value != value => {
+                    resolution.disagreements.push((layer, value));
+                }
+                Some(_) => {}
+                None => {
+                    resolution = Some(OffsetResolution {
+                        layer,
+                        value,
+                        disagreements: Default::default(),
+                    })
+                }
+            }
+        }
+
+        let resolution = resolution?;
+        let value = resolution.value;
+
+        let mut resolutions = self.resolutions.lock().unwrap();
+        if !resolutions.contains_key(&CachedOffset::from(*offset)) {
+            /* only report disagreements once per offset */
+            for (layer, layer_value) in resolution.disagreements.iter() {
+                log::warn!(
+                    "Schema offset {}::{}.{} disagrees: {} says 0x{:X}, {} says 0x{:X}",
+                    offset.module,
+                    offset.class_name,
+                    offset.member,
+                    resolution.layer,
+                    resolution.value,
+                    layer,
+                    layer_value
+                );
+            }
+        }
+        resolutions.insert(CachedOffset::from(*offset), resolution);
+
+        Some(value)
+    }
+}
+
+/// Snapshot of all offset resolutions of a `LayeredSchemaProvider`
+pub struct SchemaDiagnostics {
+    pub layers: Vec<&'static str>,
+    pub resolutions: BTreeMap<CachedOffset, OffsetResolution>,
+}
+
+impl SchemaDiagnostics {
+    /// Number of resolved offsets per layer
+    pub fn layer_counts(&self) -> Vec<(&'static str, usize)> {
+        self.layers
+            .iter()
+            .map(|layer| {
+                (
+                    *layer,
+                    self.resolutions
+                        .values()
+                        .filter(|resolution| resolution.layer == *layer)
+                        .count(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn disagreements(&self) -> impl Iterator<Item = (&CachedOffset, &OffsetResolution)> {
+        self.resolutions
+            .iter()
+            .filter(|(_, resolution)| !resolution.disagreements.is_empty())
+    }
+}
+
+impl SchemaDiagnostics {
+    pub fn log_summary(&self) {
+        log::info!(
+            "Schema offsets by source: {}",
+            self.layer_counts()
+                .iter()
+                .map(|(layer, count)| format!("{} {}", layer, count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let disagreements = self.disagreements().count();
+        if disagreements > 0 {
+            log::warn!(
+                "{} schema offsets differ between their sources. The schema file might be outdated.",
+                disagreements
+            );
+        }
+
+        log::debug!("{}", self);
+    }
+}
+
+impl Display for SchemaDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Schema offsets ({} resolved)", self.resolutions.len())?;
+        for (layer, count) in self.layer_counts() {
+            writeln!(f, "  {:<10} {}", layer, count)?;
+        }
+
+        writeln!(f)?;
+        for (offset, resolution) in self.resolutions.iter() {
+            write!(
+                f,
+                "{}::{}.{} = 0x{:X} ({})",
+                offset.module, offset.class, offset.member, resolution.value, resolution.layer
+            )?;
+
+            for (layer, value) in resolution.disagreements.iter() {
+                write!(f, " [{} says 0x{:X}]", layer, value)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Setup a layered schema provider resolving offsets from the CS2 schema system
+/// and the schema file (if given), in that order.
+///
+/// Offsets unknown to all layers are left unresolved, so the resolver
+/// falls back to the compiled in defaults and records them as unresolved.
+///
+/// If an offset cache is given, the CS2 schema system offsets will be
+/// loaded from the cache for known builds and stored for new builds.
+pub fn setup_layered_schema(
+    states: &mut StateRegistry,
+    schema_file: Option<&Path>,
+    offset_cache: Option<&OffsetCache>,
+) -> anyhow::Result<Arc<LayeredSchemaProvider>> {
+    /* the schema file needs to be loaded first as it might contain the schema system offset */
+    let file_provider = match schema_file {
+        Some(file) => Some(load_schema_file(states, file)?),
+        None => None,
+    };
+
+    let cached_provider = match offset_cache {
+        Some(offset_cache) => offset_cache.load(states).unwrap_or_else(|err| {
+            log::warn!("Failed to load the offset cache: {:#}", err);
+            None
+        }),
+        None => None,
+    };
+
+    let mut provider = LayeredSchemaProvider::new();
+    if let Some(cached_provider) = cached_provider {
+        log::info!("Using cached CS2 schema system offsets");
+        provider = provider.with_layer("cache", Box::new(cached_provider));
+    } else {
+        match RuntimeSchemaProvider::new(states) {
+            Ok(runtime_provider) => {
+                if let Some(offset_cache) = offset_cache {
+                    if let Err(err) = offset_cache.store(states, runtime_provider.offsets()) {
+                        log::warn!("Failed to update the offset cache: {:#}", err);
+                    }
+                }
+
+                provider = provider.with_layer("runtime", Box::new(runtime_provider));
+            }
+            Err(err) => log::warn!("Failed to load the runtime schema: {:#}", err),
+        }
+    }
+
+    if let Some(file_provider) = file_provider {
+        provider = provider.with_layer("file", Box::new(file_provider));
+    }
+
+    let provider = Arc::new(provider);
+    cs2_schema_provider::setup_provider(Box::new(provider.clone()));
+    Ok(provider)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use cs2_schema_provider::{
+        OffsetInfo,
+        SchemaProvider,
+    };
+
+    use super::LayeredSchemaProvider;
+    use crate::CachedOffset;
+
+    /// Provider knowing a fixed set of members
+    struct StubProvider(BTreeMap<&'static str, u64>);
+
+    impl StubProvider {
+        fn create(offsets: &[(&'static str, u64)]) -> Box<Self> {
+            Box::new(Self(offsets.iter().cloned().collect()))
+        }
+    }
+
+    impl SchemaProvider for StubProvider {
+        fn resolve_offset(&self, offset: &OffsetInfo) -> Option<u64> {
+            self.0.get(offset.member).cloned()
+        }
+    }
+
+    fn offset_info(member: &'static str) -> OffsetInfo {
+        OffsetInfo {
+            default_value: 0xFF,
+            module: "client.dll",
+            class_name: "C_BaseEntity",
+            member,
+        }
+    }
+
+    fn create_provider() -> LayeredSchemaProvider {
+        LayeredSchemaProvider::new()
+            .with_layer(
+                "runtime",
+                StubProvider::create(&[("m_iHealth", 0x10), ("m_iTeamNum", 0x20)]),
+            )
+            .with_layer(
+                "file",
+                StubProvider::create(&[
+                    ("m_iHealth", 0x10),
+                    ("m_iTeamNum", 0x24),
+                    ("m_fFlags", 0x30),
+                ]),
+            )
+    }
+
+    #[test]
+    fn test_layer_order() {
+        let provider = create_provider();
+        assert_eq!(provider.layer_names(), vec!["runtime", "file"]);
+
+        assert_eq!(
+            provider.resolve_offset(&offset_info("m_iTeamNum")),
+            Some(0x20)
+        );
+        assert_eq!(
+            provider.resolve_offset(&offset_info("m_fFlags")),
+            Some(0x30)
+        );
+
+        let diagnostics = provider.diagnostics();
+        assert_eq!(
+            diagnostics.layer_counts(),
+            vec![("runtime", 1), ("file", 1)]
+        );
+        assert_eq!(
+            diagnostics.resolutions[&CachedOffset::from(offset_info("m_fFlags"))].layer,
+            "file"
+        );
+    }
+
+    #[test]
+    fn test_disagreements() {
+        let provider = create_provider();
+        assert_eq!(
+            provider.resolve_offset(&offset_info("m_iHealth")),
+            Some(0x10)
+        );
+        assert_eq!(
+            provider.resolve_offset(&offset_info("m_iTeamNum")),
+            Some(0x20)
+        );
+
+        let diagnostics = provider.diagnostics();
+        let disagreements = diagnostics.disagreements().collect::<Vec<_>>();
+        assert_eq!(disagreements.len(), 1);
+
+        let (offset, resolution) = disagreements[0];
+        assert_eq!(offset.member, "m_iTeamNum");
+        assert_eq!(resolution.layer, "runtime");
+        assert_eq!(resolution.disagreements, vec![("file", 0x24)]);
+    }
+
+    #[test]
+    fn test_no_source() {
+        let provider = create_provider();
+        assert_eq!(provider.resolve_offset(&offset_info("m_unknown")), None);
+
+        /* unresolved offsets are left to the resolver fallback */
+        let diagnostics = provider.diagnostics();
+        assert!(diagnostics.resolutions.is_empty());
+        assert_eq!(
+            diagnostics.layer_counts(),
+            vec![("runtime", 0), ("file", 0)]
+        );
+    }
+}
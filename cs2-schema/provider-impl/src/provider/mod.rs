@@ -7,5 +7,11 @@ pub use cached::*;
 mod file;
 pub use file::*;
 
+mod layered;
+pub use layered::*;
+
+mod offset_cache;
+pub use offset_cache::*;
+
 mod runtime;
 pub use runtime::*;
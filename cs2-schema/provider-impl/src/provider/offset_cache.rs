@@ -0,0 +1,403 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    ops::ControlFlow,
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use cs2::{
+    schema::CSchemaSystem,
+    CS2Offset,
+    Module,
+    StateBuildInfo,
+    StateCS2Handle,
+    StateCS2Memory,
+    StatePredefinedOffset,
+    StateResolvedOffset,
+};
+use cs2_schema_definition::{
+    ClassDefinition,
+    ClassField,
+    DumpedSchema,
+    SchemaScope,
+};
+use raw_struct::Reference;
+use utils_state::StateRegistry;
+
+use super::{
+    visit_schema_fields,
+    CachedOffset,
+    CachedSchemaProvider,
+};
+
+/// Schema field which will be compared against the runtime schema
+/// before using cached schema offsets.
+const SENTINEL_FIELD: (&str, &str, &str) = ("client.dll", "C_BaseEntity", "m_iHealth");
+
+/// Persists the resolved module offsets and the runtime schema offsets
+/// for each CS2 build, so they do not need to be resolved on every launch.
+///
+/// Cache files use the schema file format and are keyed by the CS2 build revision.
+pub struct OffsetCache {
+    directory: PathBuf,
+}
+
+impl OffsetCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// Default cache directory next to the current executable
+    pub fn default_directory() -> anyhow::Result<PathBuf> {
+        let exe_file = std::env::current_exe().context("missing current exe path")?;
+        let base_dir = exe_file.parent().context("could not get exe directory")?;
+
+        Ok(base_dir.join("offset-cache"))
+    }
+
+    fn cache_file(&self, revision: &str) -> PathBuf {
+        let revision = revision
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+
+        self.directory.join(format!("offsets-{}.json", revision))
+    }
+
+    /// Load the cached offsets for the current CS2 build.
+    /// Cached module offsets will be registered as predefined offsets.
+    ///
+    /// Returns `None` if there are no (valid) cached offsets for the current build.
+    pub fn load(&self, states: &mut StateRegistry) -> anyhow::Result<Option<CachedSchemaProvider>> {
+        let (revision, build_datetime) = {
+            let build_info = states.resolve::<StateBuildInfo>(())?;
+            (
+                build_info.revision.clone(),
+                build_info.build_datetime.clone(),
+            )
+        };
+
+        let cache_file = self.cache_file(&revision);
+        if !cache_file.exists() {
+            log::debug!("No offset cache for CS2 revision {}", revision);
+            return Ok(None);
+        }
+
+        let cache = DumpedSchema::from_file(&cache_file)?;
+        if cache.cs2_revision != revision || cache.cs2_build_datetime != build_datetime {
+            log::debug!(
+                "Offset cache {} is for another CS2 build ({} / {})",
+                cache_file.display(),
+                cache.cs2_revision,
+                cache.cs2_build_datetime
+            );
+            return Ok(None);
+        }
+
+        let offsets = read_schema_offsets(&cache);
+        if let Err(err) = verify_sentinels(states, &cache.resolved_offsets, &offsets) {
+            log::warn!(
+                "Offset cache {} is invalid: {:#}",
+                cache_file.display(),
+                err
+            );
+            return Ok(None);
+        }
+
+        for offset in CS2Offset::available_offsets() {
+            if let Some(value) = cache.resolved_offsets.get(offset.cache_name()).cloned() {
+                let predefined_offset = StatePredefinedOffset::new(states, *offset, value)
+                    .with_context(|| format!("resolving cached offset {}", offset.cache_name()))?;
+
+                let _ = states.set(predefined_offset, *offset);
+            }
+        }
+
+        log::debug!(
+            "Loaded {} module and {} schema offsets from {}",
+            cache.resolved_offsets.len(),
+            offsets.len(),
+            cache_file.display()
+        );
+        Ok(Some(CachedSchemaProvider::new(offsets)))
+    }
+
+    /// Store the module offsets and the given schema offsets for the current CS2 build.
+    pub fn store(
+        &self,
+        states: &StateRegistry,
+        schema_offsets: &BTreeMap<CachedOffset, u64>,
+    ) -> anyhow::Result<()> {
+        let (revision, build_datetime) = {
+            let build_info = states.resolve::<StateBuildInfo>(())?;
+            (
+                build_info.revision.clone(),
+                build_info.build_datetime.clone(),
+            )
+        };
+
+        let mut module_offsets = BTreeMap::new();
+        for offset in CS2Offset::available_offsets() {
+            match states.resolve::<StateResolvedOffset>(*offset) {
+                Ok(resolved) => {
+                    module_offsets.insert(offset.cache_name().to_string(), resolved.offset);
+                }
+                Err(err) => log::debug!(
+                    "Skipping offset {} for the offset cache: {:#}",
+                    offset.cache_name(),
+                    err
+                ),
+            }
+        }
+
+        let cache = create_cache(revision, build_datetime, module_offsets, schema_offsets);
+        fs::create_dir_all(&self.directory)
+            .with_context(|| format!("create {}", self.directory.display()))?;
+
+        let cache_file = self.cache_file(&cache.cs2_revision);
+        cache.to_file(&cache_file)?;
+
+        log::debug!("Offset cache written to {}", cache_file.display());
+        Ok(())
+    }
+}
+
+fn create_cache(
+    revision: String,
+    build_datetime: String,
+    module_offsets: BTreeMap<String, u64>,
+    schema_offsets: &BTreeMap<CachedOffset, u64>,
+) -> DumpedSchema {
+    let mut cache = DumpedSchema {
+        cs2_revision: revision,
+        cs2_build_datetime: build_datetime,
+        resolved_offsets: module_offsets,
+        ..Default::default()
+    };
+
+    for (offset, value) in schema_offsets.iter() {
+        let scope_index = match cache
+            .scopes
+            .iter()
+            .position(|scope| scope.schema_name == offset.module)
+        {
+            Some(index) => index,
+            None => {
+                cache.scopes.push(SchemaScope {
+                    schema_name: offset.module.clone(),
+                    ..Default::default()
+                });
+                cache.scopes.len() - 1
+            }
+        };
+
+        /* offsets are ordered by module and class, therefore the class is the last one if present */
+        let classes = &mut cache.scopes[scope_index].classes;
+        if classes
+            .last()
+            .is_none_or(|class| class.class_name != offset.class)
+        {
+            classes.push(ClassDefinition {
+                schema_scope_name: offset.module.clone(),
+                class_name: offset.class.clone(),
+                ..Default::default()
+            });
+        }
+
+        if let Some(class) = classes.last_mut() {
+            class.offsets.push(ClassField {
+                field_name: offset.member.clone(),
+                offset: *value,
+                ..Default::default()
+            });
+        }
+    }
+
+    cache
+}
+
+fn read_schema_offsets(cache: &DumpedSchema) -> BTreeMap<CachedOffset, u64> {
+    let mut offsets = BTreeMap::<CachedOffset, u64>::new();
+    for class in cache.scopes.iter().flat_map(|scope| scope.classes.iter()) {
+        for field in class.offsets.iter() {
+            offsets.insert(
+                CachedOffset {
+                    module: class.schema_scope_name.clone(),
+                    class: class.class_name.clone(),
+                    member: field.field_name.clone(),
+                },
+                field.offset,
+            );
+        }
+    }
+
+    offsets
+}
+
+/// Verify a few offsets of the cache against the current CS2 instance
+fn verify_sentinels(
+    states: &StateRegistry,
+    module_offsets: &BTreeMap<String, u64>,
+    schema_offsets: &BTreeMap<CachedOffset, u64>,
+) -> anyhow::Result<()> {
+    /* the build info offset has already been resolved in order to identify the build */
+    let build_info = states.resolve::<StateResolvedOffset>(CS2Offset::BuildInfo)?;
+    if module_offsets.get(CS2Offset::BuildInfo.cache_name()) != Some(&build_info.offset) {
+        anyhow::bail!("build info offset does not match");
+    }
+
+    let schema_system_address = {
+        let cs2 = states.resolve::<StateCS2Handle>(())?;
+        let memory = states.resolve::<StateCS2Memory>(())?;
+
+        let offset = module_offsets
+            .get(CS2Offset::SchemaSystem.cache_name())
+            .context("missing schema system offset")?;
+
+        let address = cs2.memory_address(Module::Schemasystem, *offset)?;
+        let schema_system = Reference::<dyn CSchemaSystem>::new(memory.view_arc(), address);
+        let scope_count = schema_system.scopes()?.size()?;
+        if scope_count == 0 || scope_count > 0x20 {
+            anyhow::bail!("schema system contains {} scopes", scope_count);
+        }
+
+        address
+    };
+
+    /* spot check a well known schema field against the runtime schema */
+    let (module, class, member) = SENTINEL_FIELD;
+    let sentinel = CachedOffset {
+        module: module.to_string(),
+        class: class.to_string(),
+        member: member.to_string(),
+    };
+    let cached_value = schema_offsets
+        .get(&sentinel)
+        .with_context(|| format!("missing schema offset {}::{}.{}", module, class, member))?;
+
+    let mut runtime_value = None;
+    visit_schema_fields(
+        states,
+        schema_system_address,
+        |scope_name, class_name| scope_name == module && class_name == class,
+        |offset, value| {
+            if offset != sentinel {
+                return ControlFlow::Continue(());
+            }
+
+            runtime_value = Some(value);
+            ControlFlow::Break(())
+        },
+    )?;
+
+    match runtime_value {
+        Some(value) if value == *cached_value => Ok(()),
+        Some(value) => anyhow::bail!(
+            "schema offset {}::{}.{} changed from 0x{:X} to 0x{:X}",
+            module,
+            class,
+            member,
+            cached_value,
+            value
+        ),
+        None => anyhow::bail!(
+            "schema offset {}::{}.{} not found in the runtime schema",
+            module,
+            class,
+            member
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::BTreeMap,
+        fs,
+        path::Path,
+    };
+
+    use cs2_schema_definition::DumpedSchema;
+
+    use super::{
+        create_cache,
+        read_schema_offsets,
+        OffsetCache,
+    };
+    use crate::CachedOffset;
+
+    fn cached_offset(module: &str, class: &str, member: &str) -> CachedOffset {
+        CachedOffset {
+            module: module.to_string(),
+            class: class.to_string(),
+            member: member.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cache_file_name() {
+        let cache = OffsetCache::new("offset-cache");
+        assert_eq!(
+            cache.cache_file("14050"),
+            Path::new("offset-cache").join("offsets-14050.json")
+        );
+        assert_eq!(
+            cache.cache_file("../1.2 b"),
+            Path::new("offset-cache").join("offsets-___1_2_b.json")
+        );
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let module_offsets = BTreeMap::from([
+            ("build-info".to_string(), 0x1000),
+            ("schema-system".to_string(), 0x2000),
+        ]);
+        let schema_offsets = BTreeMap::from([
+            (
+                cached_offset("!GlobalTypes", "CEntityIdentity", "m_name"),
+                0x18,
+            ),
+            (
+                cached_offset("client.dll", "C_BaseEntity", "m_iHealth"),
+                0x344,
+            ),
+            (
+                cached_offset("client.dll", "C_BaseEntity", "m_iTeamNum"),
+                0x3E3,
+            ),
+            (
+                cached_offset("client.dll", "C_CSPlayerPawn", "m_ArmorValue"),
+                0x1508,
+            ),
+        ]);
+
+        let directory =
+            std::env::temp_dir().join(format!("offset-cache-test-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let offset_cache = OffsetCache::new(&directory);
+        let cache_file = offset_cache.cache_file("14050");
+        create_cache(
+            "14050".to_string(),
+            "Jan 01 2025 12:00:00".to_string(),
+            module_offsets.clone(),
+            &schema_offsets,
+        )
+        .to_file(&cache_file)
+        .unwrap();
+
+        let cache = DumpedSchema::from_file(&cache_file).unwrap();
+        let _ = fs::remove_dir_all(&directory);
+
+        assert_eq!(cache.cs2_revision, "14050");
+        assert_eq!(cache.cs2_build_datetime, "Jan 01 2025 12:00:00");
+        assert_eq!(cache.resolved_offsets, module_offsets);
+        assert_eq!(cache.scopes.len(), 2);
+        assert_eq!(cache.scopes[1].classes.len(), 2);
+        assert_eq!(read_schema_offsets(&cache), schema_offsets);
+    }
+}
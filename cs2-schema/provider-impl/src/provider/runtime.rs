@@ -1,6 +1,9 @@
 use std::{
     collections::BTreeMap,
-    ops::Deref,
+    ops::{
+        ControlFlow,
+        Deref,
+    },
 };
 
 use anyhow::Context;
@@ -34,95 +37,129 @@ pub struct RuntimeSchemaProvider {
 
 impl RuntimeSchemaProvider {
     pub fn new(states: &StateRegistry) -> anyhow::Result<Self> {
-        let cs2 = states.resolve::<StateCS2Handle>(())?;
-        let memory = states.resolve::<StateCS2Memory>(())?;
-
         let schema_system = states.resolve::<StateResolvedOffset>(CS2Offset::SchemaSystem)?;
-        let system_instance =
-            Reference::<dyn CSchemaSystem>::new(memory.view_arc(), schema_system.address);
 
-        let scopes = system_instance.scopes()?;
-        let scope_size = scopes.size()? as usize;
-        log::debug!(
-            "Schema system located at 0x{:X} (0x{:X}) containing 0x{:X} scopes",
+        let mut offsets = BTreeMap::<CachedOffset, u64>::new();
+        visit_schema_fields(
+            states,
             schema_system.address,
-            cs2.module_address(Module::Schemasystem, schema_system.address)
-                .context("invalid schema system address")?,
-            scope_size
-        );
+            |_scope_name, _class_name| true,
+            |offset, value| {
+                offsets.insert(offset, value);
+                ControlFlow::Continue(())
+            },
+        )?;
 
-        if scope_size > 0x20 {
-            anyhow::bail!("Too many scopes ({}). Something went wrong?", scope_size);
-        }
+        Ok(Self {
+            inner: CachedSchemaProvider::new(offsets),
+        })
+    }
+}
 
-        let mut offsets = BTreeMap::<CachedOffset, u64>::new();
-        for scope_ptr in scopes
-            .data()?
-            .elements(memory.view(), 0..scopes.size()? as usize)?
-        {
-            let scope = scope_ptr
+/// Walk the client classes of the CS2 schema system located at `schema_system_address`.
+///
+/// Fields will only be read for classes where `visit_class` returns true.
+/// The walk stops as soon as `visit_field` breaks.
+pub(crate) fn visit_schema_fields(
+    states: &StateRegistry,
+    schema_system_address: u64,
+    mut visit_class: impl FnMut(&str, &str) -> bool,
+    mut visit_field: impl FnMut(CachedOffset, u64) -> ControlFlow<()>,
+) -> anyhow::Result<()> {
+    let cs2 = states.resolve::<StateCS2Handle>(())?;
+    let memory = states.resolve::<StateCS2Memory>(())?;
+
+    let system_instance =
+        Reference::<dyn CSchemaSystem>::new(memory.view_arc(), schema_system_address);
+
+    let scopes = system_instance.scopes()?;
+    let scope_size = scopes.size()? as usize;
+    log::debug!(
+        "Schema system located at 0x{:X} (0x{:X}) containing 0x{:X} scopes",
+        schema_system_address,
+        cs2.module_address(Module::Schemasystem, schema_system_address)
+            .context("invalid schema system address")?,
+        scope_size
+    );
+
+    if scope_size > 0x20 {
+        anyhow::bail!("Too many scopes ({}). Something went wrong?", scope_size);
+    }
+
+    for scope_ptr in scopes
+        .data()?
+        .elements(memory.view(), 0..scopes.size()? as usize)?
+    {
+        let scope = scope_ptr
+            .value_copy(memory.view())?
+            .context("scope nullptr")?;
+
+        let scope_name = scope.scope_name()?.to_string_lossy().to_string();
+        log::trace!("Name: {} @ {:X}", scope_name, scope_ptr.address);
+
+        let declared_classes = scope.type_declared_class()?;
+        let declared_classes = declared_classes.elements()?.elements_copy(
+            memory.view(),
+            0..declared_classes.highest_entry()?.wrapping_add(1) as usize,
+        )?;
+
+        for rb_node in declared_classes {
+            let declared_class = rb_node
+                .value()?
+                .value
+                .cast::<dyn CSchemaTypeDeclaredClass>()
+                .value_reference(memory.view_arc())
+                .context("tree null entry")?;
+
+            let schema_class = declared_class.declaration()?;
+            let binding = schema_class
                 .value_copy(memory.view())?
-                .context("scope nullptr")?;
-
-            let scope_name = scope.scope_name()?.to_string_lossy().to_string();
-            log::trace!("Name: {} @ {:X}", scope_name, scope_ptr.address);
-
-            let declared_classes = scope.type_declared_class()?;
-            let declared_classes = declared_classes.elements()?.elements_copy(
-                memory.view(),
-                0..declared_classes.highest_entry()?.wrapping_add(1) as usize,
-            )?;
-
-            for rb_node in declared_classes {
-                let declared_class = rb_node
-                    .value()?
-                    .value
-                    .cast::<dyn CSchemaTypeDeclaredClass>()
-                    .value_reference(memory.view_arc())
-                    .context("tree null entry")?;
-
-                let schema_class = declared_class.declaration()?;
-                let binding = schema_class
-                    .value_copy(memory.view())?
-                    .context("class declaration ptr null")?;
-
-                let (class_type_scope_name, class_name) =
-                    cs2::read_class_scope_and_name(states, binding.deref())?;
-                log::trace!(
-                    "   {:X} {} -> {}",
-                    schema_class.address,
-                    class_name,
-                    class_type_scope_name
-                );
-                if !["client.dll", "!GlobalTypes"].contains(&class_type_scope_name.as_str()) {
-                    continue;
-                }
+                .context("class declaration ptr null")?;
+
+            let (class_type_scope_name, class_name) =
+                cs2::read_class_scope_and_name(states, binding.deref())?;
+            log::trace!(
+                "   {:X} {} -> {}",
+                schema_class.address,
+                class_name,
+                class_type_scope_name
+            );
+            if !["client.dll", "!GlobalTypes"].contains(&class_type_scope_name.as_str()) {
+                continue;
+            }
+
+            if !visit_class(&class_type_scope_name, &class_name) {
+                continue;
+            }
 
-                for class_member in binding
-                    .fields()?
-                    .elements(memory.view(), 0..binding.field_size()? as usize)?
-                {
-                    let member_name = class_member
-                        .name()?
-                        .read_string(memory.view())?
-                        .context("missing class member name")?;
-                    let member_offset = class_member.offset()? as u64;
-
-                    offsets.insert(
-                        CachedOffset {
-                            module: class_type_scope_name.clone(),
-                            class: class_name.clone(),
-                            member: member_name,
-                        },
-                        member_offset,
-                    );
+            for class_member in binding
+                .fields()?
+                .elements(memory.view(), 0..binding.field_size()? as usize)?
+            {
+                let member_name = class_member
+                    .name()?
+                    .read_string(memory.view())?
+                    .context("missing class member name")?;
+                let member_offset = class_member.offset()? as u64;
+
+                let offset = CachedOffset {
+                    module: class_type_scope_name.clone(),
+                    class: class_name.clone(),
+                    member: member_name,
+                };
+                if visit_field(offset, member_offset).is_break() {
+                    return Ok(());
                 }
             }
         }
+    }
 
-        Ok(Self {
-            inner: CachedSchemaProvider::new(offsets),
-        })
+    Ok(())
+}
+
+impl RuntimeSchemaProvider {
+    pub fn offsets(&self) -> &BTreeMap<CachedOffset, u64> {
+        self.inner.offsets()
     }
 }
 
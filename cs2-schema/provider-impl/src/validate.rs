@@ -0,0 +1,33 @@
+use cs2_schema_provider::OffsetInfo;
+
+/// Validate all offsets used by the generated schema bindings
+/// against the current schema provider and log every offset which is missing.
+///
+/// Returns the unresolved offsets.
+pub fn validate_runtime_offsets() -> Vec<OffsetInfo> {
+    let missing = cs2_schema_provider::validate_offsets(cs2_schema_generated::cs2::RUNTIME_OFFSETS);
+    if missing.is_empty() {
+        log::debug!(
+            "All {} schema offsets resolved",
+            cs2_schema_generated::cs2::RUNTIME_OFFSETS.len()
+        );
+        return missing;
+    }
+
+    log::warn!(
+        "{} of {} schema offsets could not be resolved:",
+        missing.len(),
+        cs2_schema_generated::cs2::RUNTIME_OFFSETS.len()
+    );
+    for offset in missing.iter() {
+        log::warn!(
+            " - {}::{}.{} (fallback 0x{:X})",
+            offset.module,
+            offset.class_name,
+            offset.member,
+            offset.default_value
+        );
+    }
+
+    missing
+}
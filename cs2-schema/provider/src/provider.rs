@@ -1,6 +1,13 @@
-use std::sync::RwLock;
+use std::{
+    collections::BTreeSet,
+    sync::{
+        Arc,
+        Mutex,
+        RwLock,
+    },
+};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct OffsetInfo {
     pub default_value: u64,
     pub module: &'static str,
@@ -12,9 +19,63 @@ pub trait SchemaProvider: Send + Sync {
     fn resolve_offset(&self, offset: &OffsetInfo) -> Option<u64>;
 }
 
+impl<T: SchemaProvider + ?Sized> SchemaProvider for Arc<T> {
+    fn resolve_offset(&self, offset: &OffsetInfo) -> Option<u64> {
+        (**self).resolve_offset(offset)
+    }
+}
+
+/// Behaviour when an offset can not be resolved by the schema provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnresolvedOffsetPolicy {
+    /// Use the offset from the schema the bindings have been generated with.
+    /// A warning will be logged and the offset recorded as unresolved.
+    #[default]
+    Fallback,
+
+    /// Panic on unresolved offsets
+    Panic,
+}
+
+pub(crate) static UNRESOLVED_OFFSET_POLICY: RwLock<UnresolvedOffsetPolicy> =
+    RwLock::new(UnresolvedOffsetPolicy::Fallback);
+
+pub(crate) static UNRESOLVED_OFFSETS: Mutex<BTreeSet<OffsetInfo>> = Mutex::new(BTreeSet::new());
+
 pub(crate) static PROVIDER_INSTANCE: RwLock<Option<Box<dyn SchemaProvider>>> = RwLock::new(None);
 
 pub fn setup_provider(provider: Box<dyn SchemaProvider>) {
     let mut instance = PROVIDER_INSTANCE.write().unwrap();
     *instance = Some(provider);
 }
+
+pub fn set_unresolved_offset_policy(policy: UnresolvedOffsetPolicy) {
+    *UNRESOLVED_OFFSET_POLICY.write().unwrap() = policy;
+}
+
+/// All offsets which could not be resolved so far
+pub fn unresolved_offsets() -> Vec<OffsetInfo> {
+    UNRESOLVED_OFFSETS.lock().unwrap().iter().cloned().collect()
+}
+
+/// Try to resolve all given offsets with the current schema provider.
+/// Returns every offset which could not be resolved.
+pub fn validate_offsets(offsets: &[OffsetInfo]) -> Vec<OffsetInfo> {
+    let instance = PROVIDER_INSTANCE.read().unwrap();
+    let missing = offsets
+        .iter()
+        .filter(|offset| {
+            instance
+                .as_ref()
+                .and_then(|instance| instance.resolve_offset(offset))
+                .is_none()
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    UNRESOLVED_OFFSETS
+        .lock()
+        .unwrap()
+        .extend(missing.iter().cloned());
+    missing
+}
@@ -1,6 +1,9 @@
 use crate::{
     OffsetInfo,
+    UnresolvedOffsetPolicy,
     PROVIDER_INSTANCE,
+    UNRESOLVED_OFFSETS,
+    UNRESOLVED_OFFSET_POLICY,
 };
 
 #[macro_export]
@@ -29,15 +32,102 @@ pub fn resolve_offset(cache: &mut Option<u64>, offset: &OffsetInfo) -> u64 {
             offset.member
         );
         let instance = PROVIDER_INSTANCE.read().unwrap();
-        let Some(instance) = instance.as_ref() else {
-            panic!("no schema provider set");
+        let value = match instance.as_ref() {
+            Some(instance) => instance.resolve_offset(offset),
+            None => {
+                log::warn!("No schema provider set");
+                None
+            }
         };
 
-        let Some(value) = instance.resolve_offset(offset) else {
-            panic!("could not resolve offset for {:?}", offset);
+        let value = match value {
+            Some(value) => value,
+            None => resolve_fallback(offset),
         };
 
         log::trace!(" -> 0x{:X}", value);
         value
     })
 }
+
+fn resolve_fallback(offset: &OffsetInfo) -> u64 {
+    UNRESOLVED_OFFSETS.lock().unwrap().insert(*offset);
+
+    match *UNRESOLVED_OFFSET_POLICY.read().unwrap() {
+        UnresolvedOffsetPolicy::Panic => panic!("could not resolve offset for {:?}", offset),
+        UnresolvedOffsetPolicy::Fallback => {
+            log::warn!(
+                "Could not resolve offset {}::{}.{}. Using fallback 0x{:X}.",
+                offset.module,
+                offset.class_name,
+                offset.member,
+                offset.default_value
+            );
+            offset.default_value
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        panic,
+        sync::Mutex,
+    };
+
+    use super::resolve_offset;
+    use crate::{
+        set_unresolved_offset_policy,
+        setup_provider,
+        unresolved_offsets,
+        OffsetInfo,
+        SchemaProvider,
+        UnresolvedOffsetPolicy,
+    };
+
+    /// The provider and policy are global, hence tests must not run concurrently
+    static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    struct EmptyProvider;
+    impl SchemaProvider for EmptyProvider {
+        fn resolve_offset(&self, _offset: &OffsetInfo) -> Option<u64> {
+            None
+        }
+    }
+
+    fn offset_info(member: &'static str) -> OffsetInfo {
+        OffsetInfo {
+            default_value: 0x10,
+            module: "client.dll",
+            class_name: "C_BaseEntity",
+            member,
+        }
+    }
+
+    #[test]
+    fn test_unresolved_fallback() {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+        setup_provider(Box::new(EmptyProvider));
+        set_unresolved_offset_policy(UnresolvedOffsetPolicy::Fallback);
+
+        let offset = offset_info("m_iHealth");
+        let mut cache = None;
+        assert_eq!(resolve_offset(&mut cache, &offset), 0x10);
+        assert_eq!(cache, Some(0x10));
+        assert!(unresolved_offsets().contains(&offset));
+    }
+
+    #[test]
+    fn test_unresolved_panic() {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+        setup_provider(Box::new(EmptyProvider));
+        set_unresolved_offset_policy(UnresolvedOffsetPolicy::Panic);
+
+        let offset = offset_info("m_iMaxHealth");
+        let result = panic::catch_unwind(|| resolve_offset(&mut None, &offset));
+        set_unresolved_offset_policy(UnresolvedOffsetPolicy::Fallback);
+
+        assert!(result.is_err());
+        assert!(unresolved_offsets().contains(&offset));
+    }
+}
@@ -1,8 +1,16 @@
-use std::ops::Deref;
+use std::{
+    ops::Deref,
+    time::Duration,
+};
 
+use anyhow::Context;
 use cs2_schema_cutl::CStringUtil;
 use raw_struct::Reference;
-use utils_state::StateRegistry;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
 
 use crate::{
     schema::{
@@ -29,6 +37,12 @@ impl ConVars {
     }
 
     pub fn find_cvar(&self, name: &str) -> anyhow::Result<Option<Reference<dyn ConVar>>> {
+        Ok(self
+            .find_cvar_address(name)?
+            .map(|address| Reference::<dyn ConVar>::new(self.ccvars.reference_memory(), address)))
+    }
+
+    pub fn find_cvar_address(&self, name: &str) -> anyhow::Result<Option<u64>> {
         let memory_view_arc = self.ccvars.reference_memory();
         let memory_view = memory_view_arc.deref();
 
@@ -40,7 +54,8 @@ impl ConVars {
             .elements_copy(memory_view, 0..entry_count)?;
 
         for entry in entries {
-            let Some(con_var) = entry.value()?.value_reference(memory_view_arc.clone()) else {
+            let con_var_ptr = entry.value()?;
+            let Some(con_var) = con_var_ptr.value_reference(memory_view_arc.clone()) else {
                 continue;
             };
 
@@ -52,9 +67,41 @@ impl ConVars {
                 continue;
             }
 
-            return Ok(Some(con_var));
+            return Ok(Some(con_var_ptr.address));
         }
 
         Ok(None)
     }
 }
+
+/// Float value of a console variable.
+/// The value will be re-read every few seconds.
+pub struct StateConVar {
+    address: u64,
+    pub value: f32,
+}
+
+impl State for StateConVar {
+    type Parameter = &'static str;
+
+    fn create(states: &StateRegistry, name: Self::Parameter) -> anyhow::Result<Self> {
+        let address = ConVars::new(states)?
+            .find_cvar_address(name)?
+            .with_context(|| format!("missing cvar {}", name))?;
+
+        Ok(Self {
+            address,
+            value: 0.0,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Refresh(Duration::from_secs(5))
+    }
+
+    fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+        let memory = states.resolve::<StateCS2Memory>(())?;
+        self.value = Reference::<dyn ConVar>::new(memory.view_arc(), self.address).fl_value()?;
+        Ok(())
+    }
+}
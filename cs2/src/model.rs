@@ -13,10 +13,15 @@ use raw_struct::{
     FromMemoryView,
     Reference,
 };
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
     StateRegistry,
+    StateSerializer,
 };
 
 use crate::{
@@ -51,14 +56,14 @@ pub enum BoneFlags {
     FlagProcedural = 0x400000,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Bone {
     pub name: String,
     pub flags: u32,
     pub parent: Option<usize>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct CS2Model {
     pub name: String,
     pub bones: Vec<Bone>,
@@ -98,6 +103,10 @@ impl State for CS2Model {
     fn cache_type() -> StateCacheType {
         StateCacheType::Timed(Duration::from_secs(60))
     }
+
+    fn serializer() -> Option<StateSerializer> {
+        Some(StateSerializer::of::<Self>("cs2-model"))
+    }
 }
 
 impl CS2Model {
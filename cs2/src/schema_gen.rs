@@ -62,42 +62,119 @@ fn parse_metadata(
         .context("meta name")?
         .context("missing metadata name")?;
 
-    let meta = match name.as_str() {
-        "MNetworkEnable" => Metadata::NetworkEnable,
-        "MNetworkDisable" => Metadata::NetworkDisable,
-        "MNetworkChangeCallback" => {
-            let name = metadata
-                .metadata_value()?
-                .cast::<PtrCStr>()
-                .read_value(memory.view())?
-                .context("missing network change callback ptr")?
-                .read_string(memory.view())?
-                .context("missing network change callback name")?;
+    let read_string = || -> anyhow::Result<String> {
+        metadata
+            .metadata_value()?
+            .cast::<PtrCStr>()
+            .read_value(memory.view())?
+            .with_context(|| format!("missing {} ptr", name))?
+            .read_string(memory.view())?
+            .with_context(|| format!("missing {} value", name))
+    };
+    let read_i32 = || -> anyhow::Result<i32> {
+        metadata
+            .metadata_value()?
+            .cast::<i32>()
+            .read_value(memory.view())?
+            .with_context(|| format!("missing {} value", name))
+    };
+    let read_f32 = || -> anyhow::Result<f32> {
+        metadata
+            .metadata_value()?
+            .cast::<f32>()
+            .read_value(memory.view())?
+            .with_context(|| format!("missing {} value", name))
+    };
 
-            Metadata::NetworkChangeCallback { name }
+    let read_unknown = || {
+        /*
+         * The value type of unknown entries is not known.
+         * Keep the first 8 bytes of the value (an integer, float or pointer) to document it.
+         */
+        let value = metadata
+            .metadata_value()
+            .ok()
+            .and_then(|value| value.read_value(memory.view()).ok().flatten())
+            .map(|value| value.to_le_bytes().to_vec())
+            .unwrap_or_default();
+
+        Metadata::Unknown {
+            name: name.clone(),
+            value,
         }
-        "MNetworkVarNames" => {
-            let meta_value = metadata
-                .metadata_value()?
-                .cast::<dyn CSchemaMetadataVarNames>()
-                .value_copy(memory.view())?
-                .context("missing network var names")?;
-
-            Metadata::NetworkVarNames {
-                var_name: meta_value
-                    .var_name()?
-                    .read_string(memory.view())?
-                    .context("missing var name")?,
-                var_type: meta_value
-                    .var_type()?
-                    .read_string(memory.view())?
-                    .context("missing var type")?,
+    };
+
+    let parse_typed = || -> anyhow::Result<Metadata> {
+        let meta = match name.as_str() {
+            "MNetworkEnable" => Metadata::NetworkEnable,
+            "MNetworkDisable" => Metadata::NetworkDisable,
+            "MNetworkChangeCallback" => Metadata::NetworkChangeCallback {
+                name: read_string()?,
+            },
+            "MNetworkVarNames" => {
+                let meta_value = metadata
+                    .metadata_value()?
+                    .cast::<dyn CSchemaMetadataVarNames>()
+                    .value_copy(memory.view())?
+                    .context("missing network var names")?;
+
+                Metadata::NetworkVarNames {
+                    var_name: meta_value
+                        .var_name()?
+                        .read_string(memory.view())?
+                        .context("missing var name")?,
+                    var_type: meta_value
+                        .var_type()?
+                        .read_string(memory.view())?
+                        .context("missing var type")?,
+                }
             }
-        }
-        _ => Metadata::Unknown { name },
+            "MNetworkEncoder" => Metadata::NetworkEncoder {
+                name: read_string()?,
+            },
+            "MNetworkSerializer" => Metadata::NetworkSerializer {
+                name: read_string()?,
+            },
+            "MNetworkAlias" => Metadata::NetworkAlias {
+                name: read_string()?,
+            },
+            "MNetworkUserGroup" => Metadata::NetworkUserGroup {
+                name: read_string()?,
+            },
+            "MNetworkPriority" => Metadata::NetworkPriority {
+                priority: read_i32()?,
+            },
+            "MNetworkBitCount" => Metadata::NetworkBitCount { bits: read_i32()? },
+            "MNetworkEncodeFlags" => Metadata::NetworkEncodeFlags { flags: read_i32()? },
+            "MNetworkMinValue" => Metadata::NetworkMinValue { value: read_f32()? },
+            "MNetworkMaxValue" => Metadata::NetworkMaxValue { value: read_f32()? },
+            "MNetworkVarEmbeddedFieldOffsetDelta" => {
+                Metadata::NetworkVarEmbeddedFieldOffsetDelta { delta: read_i32()? }
+            }
+            "MPropertyFriendlyName" => Metadata::PropertyFriendlyName {
+                name: read_string()?,
+            },
+            "MPropertyDescription" => Metadata::PropertyDescription {
+                description: read_string()?,
+            },
+            _ => read_unknown(),
+        };
+
+        Ok(meta)
     };
 
-    Ok(meta)
+    match parse_typed() {
+        Ok(meta) => Ok(meta),
+        Err(err) => {
+            /* a single malformed entry should not abort the whole schema dump */
+            log::debug!(
+                "Failed to read metadata {} ({:#}). Keeping it as unknown entry.",
+                name,
+                err
+            );
+            Ok(read_unknown())
+        }
+    }
 }
 
 fn parse_type(
@@ -112,30 +189,18 @@ fn parse_type(
                 .read_string(memory.view())?
                 .context("missing var type str")?;
 
-            let rust_type = match var_type.as_str() {
-                "bool" => "bool",
-                "char" => "u8",
-
-                "int8" => "i8",
-                "uint8" => "u8",
-
-                "int16" => "i16",
-                "uint16" => "u16",
+            let rust_type = cs2_schema_definition::map_builtin_type(&var_type)
+                .with_context(|| format!("Unknown builtin type {}", var_type))?;
 
-                "int32" => "i32",
-                "uint32" => "u32",
-
-                "int64" => "i64",
-                "uint64" => "u64",
-
-                "float32" => "f32",
-                "float64" => "f64",
-
-                var_type => anyhow::bail!("Unknown builtin type {}", var_type),
-            }
-            .to_string();
+            Some(rust_type.to_string())
+        }
+        TypeCategory::Bitfield => {
+            let var_type = schema_type
+                .var_type()?
+                .read_string(memory.view())?
+                .context("missing var type str")?;
 
-            Some(rust_type)
+            cs2_schema_definition::map_bitfield_type(&var_type).map(ToString::to_string)
         }
         TypeCategory::FixedArray => {
             let fixed_array = schema_type.cast::<dyn CSchemaTypeFixedArray>();
@@ -177,35 +242,13 @@ fn parse_type(
                         .read_string(memory.view())?
                         .context("missing var type")?;
 
-                    Some(
-                        match value.as_str() {
-                            "CEntityIndex" => "CEntityIndex",
-
-                            "CUtlStringToken" => "dyn CUtlStringToken",
-                            "CUtlSymbolLarge" => "PtrCStr",
-                            "CUtlString" => "dyn CUtlString",
-                            "Vector" => "[f32; 0x03]",
-                            "QAngle" => "[f32; 0x04]",
-
-                            "Color" => "Color", // TODO: What is this (3x or 4x f32?)?
-
-                            "CNetworkedQuantizedFloat" => "f32",
-
-                            _ => return Ok(None),
-                        }
-                        .to_string(),
-                    )
+                    cs2_schema_definition::map_atomic_type(&value).map(ToString::to_string)
                 }
                 AtomicCategory::CollectionOfT => {
                     let value = schema_type
                         .var_type()?
                         .read_string(memory.view())?
                         .context("missing var type")?;
-                    if !value.starts_with("CUtlVector<")
-                        || !value.starts_with("C_NetworkUtlVectorBase<")
-                    {
-                        return Ok(None);
-                    }
 
                     let atomic_collection =
                         schema_type.cast::<dyn CSchemaTypeAtomicCollectionOfT>();
@@ -215,15 +258,8 @@ fn parse_type(
                         .context("missing inner type")?;
                     let inner_type = parse_type(states, &inner_type)?;
 
-                    inner_type.map(|inner_type| {
-                        format!(
-                            "dyn CUtlVector<{}>",
-                            if inner_type.starts_with("dyn ") {
-                                format!("Copy<{}>", inner_type)
-                            } else {
-                                inner_type
-                            }
-                        )
+                    inner_type.and_then(|inner_type| {
+                        cs2_schema_definition::map_collection_type(&value, &inner_type)
                     })
                 }
                 AtomicCategory::T => {
@@ -232,18 +268,15 @@ fn parse_type(
                         .read_string(memory.view())?
                         .context("missing var type")?;
 
-                    if !value.starts_with("CHandle<") {
-                        return Ok(None);
-                    }
-
+                    /* resource handles do not require the inner type */
                     let atomic_t = schema_type.cast::<dyn CSchemaTypeAtomicT>();
-                    let inner_type = atomic_t
-                        .inner_type()?
-                        .value_reference(memory.view_arc())
-                        .context("missing inner type")?;
-                    let inner_type = parse_type(states, &inner_type)?;
+                    let inner_type = match atomic_t.inner_type()?.value_reference(memory.view_arc())
+                    {
+                        Some(inner_type) => parse_type(states, &inner_type)?,
+                        None => None,
+                    };
 
-                    inner_type.map(|inner_type| format!("EntityHandle<{}>", inner_type))
+                    cs2_schema_definition::map_template_type(&value, inner_type.as_deref())
                 }
                 _ => return Ok(None),
             }
@@ -6,10 +6,15 @@ use cs2_schema_generated::cs2::client::{
     C_PlantedC4,
 };
 use obfstr::obfstr;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
     StateRegistry,
+    StateSerializer,
 };
 
 use super::StateGlobals;
@@ -20,7 +25,7 @@ use crate::{
     StateEntityList,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct BombDefuser {
     /// Totoal time remaining for a successfull bomb defuse
     pub time_remaining: f32,
@@ -29,7 +34,7 @@ pub struct BombDefuser {
     pub player_name: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum PlantedC4State {
     /// Bomb is currently actively ticking
     Active {
@@ -48,6 +53,7 @@ pub enum PlantedC4State {
 }
 
 /// Information about the currently active planted C4
+#[derive(Serialize, Deserialize)]
 pub struct PlantedC4 {
     /// Planted bomb site
     /// 0 = A
@@ -164,4 +170,8 @@ impl State for PlantedC4 {
     fn cache_type() -> StateCacheType {
         StateCacheType::Volatile
     }
+
+    fn serializer() -> Option<StateSerializer> {
+        Some(StateSerializer::of::<Self>("planted-c4"))
+    }
 }
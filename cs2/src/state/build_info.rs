@@ -4,10 +4,15 @@ use raw_struct::{
     Copy,
     FromMemoryView,
 };
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
     StateRegistry,
+    StateSerializer,
 };
 
 use crate::{
@@ -17,7 +22,7 @@ use crate::{
     StateResolvedOffset,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct StateBuildInfo {
     pub revision: String,
     pub build_datetime: String,
@@ -56,4 +61,8 @@ impl State for StateBuildInfo {
     fn cache_type() -> StateCacheType {
         StateCacheType::Persistent
     }
+
+    fn serializer() -> Option<StateSerializer> {
+        Some(StateSerializer::of::<Self>("build-info"))
+    }
 }
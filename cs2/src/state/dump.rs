@@ -0,0 +1,22 @@
+use utils_state::StateDumpLoader;
+
+use crate::{
+    CS2Model,
+    PlantedC4,
+    SpectatorList,
+    StateBuildInfo,
+    StateCurrentMap,
+    StatePawnInfo,
+};
+
+/// Register all CS2 states which are included within state dumps,
+/// allowing a dump to be loaded as a fixture for reproducing issues.
+pub fn register_dump_states(loader: &mut StateDumpLoader) -> &mut StateDumpLoader {
+    loader
+        .register::<StatePawnInfo>()
+        .register::<StateBuildInfo>()
+        .register::<StateCurrentMap>()
+        .register::<SpectatorList>()
+        .register::<PlantedC4>()
+        .register::<CS2Model>()
+}
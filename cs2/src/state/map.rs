@@ -4,10 +4,15 @@ use raw_struct::{
     builtins::Ptr64,
     FromMemoryView,
 };
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
     StateRegistry,
+    StateSerializer,
 };
 
 use crate::{
@@ -17,6 +22,7 @@ use crate::{
     StateResolvedOffset,
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct StateCurrentMap {
     pub current_map: Option<String>,
 }
@@ -53,4 +59,8 @@ impl State for StateCurrentMap {
     fn cache_type() -> StateCacheType {
         StateCacheType::Volatile
     }
+
+    fn serializer() -> Option<StateSerializer> {
+        Some(StateSerializer::of::<Self>("current-map"))
+    }
 }
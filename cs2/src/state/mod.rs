@@ -15,3 +15,6 @@ pub use globals::*;
 
 mod build_info;
 pub use build_info::*;
+
+mod dump;
+pub use dump::*;
@@ -6,10 +6,15 @@ use cs2_schema_generated::cs2::client::{
     C_BasePlayerPawn,
     C_CSObserverPawn,
 };
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
     StateRegistry,
+    StateSerializer,
 };
 
 use crate::{
@@ -20,10 +25,12 @@ use crate::{
     StateLocalPlayerController,
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct SpectatorInfo {
     pub spectator_name: String,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct SpectatorList {
     pub target_entity_id: u32,
     pub spectators: Vec<SpectatorInfo>,
@@ -97,6 +104,10 @@ impl State for SpectatorList {
     fn cache_type() -> StateCacheType {
         StateCacheType::Volatile
     }
+
+    fn serializer() -> Option<StateSerializer> {
+        Some(StateSerializer::of::<Self>("spectator-list"))
+    }
 }
 
 /// Get the entity id which we're currently following
@@ -18,10 +18,15 @@ use cs2_schema_generated::cs2::client::{
     C_CSPlayerPawnBase,
     C_EconEntity,
 };
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
     StateRegistry,
+    StateSerializer,
 };
 
 use crate::{
@@ -35,7 +40,7 @@ use crate::{
     WeaponId,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatePawnInfo {
     pub controller_entity_id: Option<u32>,
     pub pawn_entity_id: u32,
@@ -140,6 +145,10 @@ impl State for StatePawnInfo {
     fn cache_type() -> StateCacheType {
         StateCacheType::Volatile
     }
+
+    fn serializer() -> Option<StateSerializer> {
+        Some(StateSerializer::of::<Self>("pawn-info"))
+    }
 }
 
 #[derive(Debug, Clone)]
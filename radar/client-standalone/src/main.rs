@@ -1,5 +1,13 @@
 use std::{
+    net::{
+        IpAddr,
+        Ipv4Addr,
+        SocketAddr,
+        ToSocketAddrs,
+        UdpSocket,
+    },
     path::PathBuf,
+    sync::Arc,
     time::Duration,
 };
 
@@ -16,11 +24,23 @@ use radar_client::{
     CS2RadarGenerator,
     DummyRadarGenerator,
     RadarGenerator,
+    SessionOptions,
     WebRadarPublisher,
 };
-use tokio::signal;
+use radar_server::{
+    create_local_transport,
+    HttpServeDirectory,
+    RadarServer,
+};
+use tokio::{
+    signal,
+    sync::RwLock,
+};
 use url::Url;
-use utils_state::StateRegistry;
+use utils_state::{
+    StateLimits,
+    StateRegistry,
+};
 
 mod arch;
 
@@ -33,11 +53,45 @@ struct Args {
     #[arg(short, long, default_value = "wss://radar.valth.run/publish")]
     publish_url: String,
 
-    /// Load the CS2 schema (offsets) from a file
-    /// instead of resolving them at runtime by the CS2 schema system.
+    /// Load the CS2 schema (offsets) from a file.
+    /// Offsets resolved at runtime by the CS2 schema system take precedence.
     #[arg(short, long)]
     schema_file: Option<PathBuf>,
 
+    /// Do not cache the resolved offsets for the current CS2 build
+    #[arg(long)]
+    no_offset_cache: bool,
+
+    /// Start an embedded radar server and publish to it instead of
+    /// a remote server. This is usefull when using the radar within the local network.
+    #[arg(long, conflicts_with = "join_session")]
+    local: bool,
+
+    /// Address the embedded radar server should listen onto
+    #[arg(long, default_value = "0.0.0.0:7229")]
+    local_address: String,
+
+    /// Static HTML file directory of the web radar served by the embedded radar server
+    #[arg(long)]
+    local_static_dir: Option<PathBuf>,
+
+    /// Join an existing session as additional publisher (team session).
+    /// Requires the contribute token of the session.
+    #[arg(long, requires = "contribute_token")]
+    join_session: Option<String>,
+
+    /// Contribute token of the session to join
+    #[arg(long)]
+    contribute_token: Option<String>,
+
+    /// Request a human readable alias for the radar session
+    #[arg(long, conflicts_with = "join_session")]
+    session_alias: Option<String>,
+
+    /// List the radar session in the servers public session listing
+    #[arg(long, conflicts_with = "join_session")]
+    discoverable: bool,
+
     /// Use a dummy generator instead of generating the radar data from CS2.
     /// This is usefull when testing the radar client without CS2.
     #[arg(long, hide = true)]
@@ -68,8 +122,94 @@ const RECONNECT_INTERVAL: &[Duration] = &[
     Duration::from_secs(60),
 ];
 
+enum PublishTarget {
+    Remote {
+        url: Url,
+    },
+    Local {
+        server: Arc<RwLock<RadarServer>>,
+
+        /// Base URL of the web radar served by the embedded server
+        base_url: Url,
+    },
+}
+
+impl PublishTarget {
+    fn session_url(&self, session: &str) -> Url {
+        let mut radar_url = match self {
+            Self::Remote { url } => url.clone(),
+            Self::Local { base_url, .. } => base_url.clone(),
+        };
+
+        radar_url.set_path(&format!("/session/{}", session));
+        if radar_url.scheme() == "wss" {
+            let _ = radar_url.set_scheme("https");
+        } else if radar_url.scheme() == "ws" {
+            let _ = radar_url.set_scheme("http");
+        }
+
+        radar_url
+    }
+}
+
+/// Resolve the address of this machine within the local network.
+/// No packets will be send, the UDP socket is only used to determine the outgoing interface.
+fn local_network_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80)).ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+async fn create_local_server(args: &Args) -> anyhow::Result<PublishTarget> {
+    let server = RadarServer::new();
+    let address = server
+        .write()
+        .await
+        .listen_http(
+            args.local_address
+                .to_socket_addrs()?
+                .next()
+                .context("invalid local server address")?,
+            if let Some(path) = args.local_static_dir.as_ref() {
+                HttpServeDirectory::Disk { path: path.clone() }
+            } else {
+                HttpServeDirectory::None
+            },
+        )
+        .await
+        .context("start local radar server")?;
+
+    let public_address = if address.ip().is_unspecified() {
+        SocketAddr::new(
+            local_network_address().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            address.port(),
+        )
+    } else {
+        address
+    };
+
+    log::info!(
+        "Local radar server started. Subscribe via ws://{}/subscribe",
+        public_address
+    );
+    if args.local_static_dir.is_none() {
+        log::info!("No web radar files provided (--local-static-dir). Only the websocket API is available.");
+    }
+
+    Ok(PublishTarget::Local {
+        server,
+        base_url: Url::parse(&format!("http://{}/", public_address))?,
+    })
+}
+
 async fn real_main(args: &Args) -> anyhow::Result<()> {
-    let url = Url::parse(&args.publish_url).context("invalid target server address")?;
+    let target = if args.local {
+        create_local_server(args).await?
+    } else {
+        PublishTarget::Remote {
+            url: Url::parse(&args.publish_url).context("invalid target server address")?,
+        }
+    };
 
     let radar_generator: Box<dyn RadarGenerator> = if args.dummy_generator {
         Box::new(DummyRadarGenerator)
@@ -87,54 +227,97 @@ async fn real_main(args: &Args) -> anyhow::Result<()> {
                 return Err(err);
             }
         };
-        let mut states = StateRegistry::new(1024 * 8);
+        let mut states = StateRegistry::with_limits(StateLimits::default());
         states.set(StateCS2Memory::new(cs2.create_memory_view()), ())?;
         states.set(StateCS2Handle::new(cs2), ())?;
 
         if let Some(file) = &args.schema_file {
             log::info!(
                 "{} {}",
-                obfstr!("Loading CS2 schema (offsets) from file"),
+                obfstr!("Loading CS2 schema (offsets) from CS2 schema system and file"),
                 file.display()
             );
-
-            cs2_schema_provider_impl::setup_schema_from_file(&mut states, file)
-                .context("file schema setup")?;
         } else {
             log::info!(
                 "{}",
                 obfstr!("Loading CS2 schema (offsets) from CS2 schema system")
             );
-            cs2_schema_provider_impl::setup_provider(Box::new(
-                cs2_schema_provider_impl::RuntimeSchemaProvider::new(&states)
-                    .context("load runtime schema")?,
-            ));
         }
+
+        let offset_cache = if args.no_offset_cache {
+            None
+        } else {
+            Some(cs2_schema_provider_impl::OffsetCache::new(
+                cs2_schema_provider_impl::OffsetCache::default_directory()?,
+            ))
+        };
+
+        let schema_provider = cs2_schema_provider_impl::setup_layered_schema(
+            &mut states,
+            args.schema_file.as_deref(),
+            offset_cache.as_ref(),
+        )
+        .context("schema setup")?;
         log::info!("CS2 schema (offsets) loaded.");
 
+        cs2_schema_provider_impl::validate_runtime_offsets();
+        schema_provider.diagnostics().log_summary();
+
         Box::new(CS2RadarGenerator::new(states)?)
     };
 
-    self::radar_publish_loop(radar_generator, &url).await
+    self::radar_publish_loop(radar_generator, &target, args).await
+}
+
+async fn radar_connect(
+    target: &PublishTarget,
+    session_auth_token: Option<String>,
+    args: &Args,
+) -> anyhow::Result<WebRadarPublisher> {
+    let options = SessionOptions {
+        session_alias: args.session_alias.clone(),
+        discoverable: args.discoverable,
+    };
+
+    match target {
+        PublishTarget::Remote { url } => match (&args.join_session, &args.contribute_token) {
+            (Some(session_id), Some(contribute_token)) => {
+                WebRadarPublisher::join(url, session_id.clone(), contribute_token.clone()).await
+            }
+            _ => WebRadarPublisher::connect(url, session_auth_token, &options).await,
+        },
+        PublishTarget::Local { server, .. } => {
+            let (tx, rx) = create_local_transport(server).await?;
+            WebRadarPublisher::create_from_transport(session_auth_token, &options, tx, rx).await
+        }
+    }
 }
 
 async fn radar_publish_loop(
     radar_generator: Box<dyn RadarGenerator>,
-    url: &Url,
+    target: &PublishTarget,
+    args: &Args,
 ) -> anyhow::Result<()> {
-    let mut radar_client = WebRadarPublisher::connect(&url, None).await?;
+    let mut radar_client = radar_connect(target, None, args).await?;
     radar_client.set_generator(radar_generator);
 
-    let mut radar_url = url.clone();
-    radar_url.set_path(&format!("/session/{}", radar_client.session_id));
-    if radar_url.scheme() == "wss" {
-        let _ = radar_url.set_scheme("https");
-    } else {
-        let _ = radar_url.set_scheme("http");
-    }
+    let radar_url = target.session_url(
+        radar_client
+            .session_alias
+            .as_ref()
+            .unwrap_or(&radar_client.session_id),
+    );
+
+    /* subscribers opening this url are allowed to place annotations */
+    let mut annotate_url = radar_url.clone();
+    annotate_url
+        .query_pairs_mut()
+        .append_pair("annotate", &radar_client.annotation_token);
 
     log::info!("Radar session {}", radar_client.session_id);
     log::info!("Available at {}", radar_url);
+    log::info!("Annotate at {}", annotate_url);
+    log::info!("Contribute token {}", radar_client.contribute_token);
     log::info!("Press CTRL+C to exit");
 
     loop {
@@ -162,7 +345,7 @@ async fn radar_publish_loop(
         let mut reconnect_index = 0;
         radar_client = loop {
             log::info!("Reconnecting...");
-            match WebRadarPublisher::connect(&url, Some(session_auth_token.clone())).await {
+            match radar_connect(target, session_auth_token.clone(), args).await {
                 Ok(publisher) => break publisher,
                 Err(error) => {
                     log::error!("Reconnect failed: {:#}", error);
@@ -1,13 +1,20 @@
 use std::{
     pin::Pin,
-    time::Duration,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use anyhow::Context;
-use radar_shared::protocol::{
-    C2SMessage,
-    ClientEvent,
-    S2CMessage,
+use radar_shared::{
+    protocol::{
+        C2SMessage,
+        ClientEvent,
+        S2CMessage,
+    },
+    RadarAnnotation,
+    RadarAnnotationKind,
 };
 use tokio::{
     self,
@@ -27,9 +34,30 @@ use crate::{
     RadarGenerator,
 };
 
+/// Options for creating a new radar session
+#[derive(Debug, Default, Clone)]
+pub struct SessionOptions {
+    /// Human readable session alias.
+    /// The server will append a numeric suffix if the alias is already taken.
+    pub session_alias: Option<String>,
+
+    /// List the session in the servers public session listing
+    pub discoverable: bool,
+}
+
 pub struct WebRadarPublisher {
     pub session_id: String,
-    pub session_auth_token: String,
+    pub session_alias: Option<String>,
+
+    /// Token to reclaim the session after a connection loss.
+    /// Only available for the session owner.
+    pub session_auth_token: Option<String>,
+    pub annotation_token: String,
+
+    /// Token which allows other publishers to join this session
+    pub contribute_token: String,
+
+    annotations: Vec<(Instant, RadarAnnotation)>,
 
     generator: Option<Box<dyn RadarGenerator>>,
     generate_interval: Pin<Box<Interval>>,
@@ -38,57 +66,143 @@ pub struct WebRadarPublisher {
     transport_rx: Receiver<ClientEvent<S2CMessage>>,
 }
 
+async fn await_init_response(
+    rx: &mut Receiver<ClientEvent<S2CMessage>>,
+) -> anyhow::Result<S2CMessage> {
+    let event = tokio::select! {
+        message = rx.recv() => message.context("unexpected client disconnect")?,
+        _ = time::sleep(Duration::from_secs(5)) => {
+            anyhow::bail!("session init timeout");
+        }
+    };
+
+    match event {
+        ClientEvent::RecvMessage(message) => match message {
+            S2CMessage::ResponseError { error } => {
+                anyhow::bail!("server error: {}", error)
+            }
+            S2CMessage::ResponseSessionInvalidId {} => {
+                anyhow::bail!("session does not exists")
+            }
+            message => Ok(message),
+        },
+        ClientEvent::RecvError(err) => anyhow::bail!("recv err: {:#}", err),
+        ClientEvent::SendError(err) => anyhow::bail!("send err: {:#}", err),
+    }
+}
+
 impl WebRadarPublisher {
-    pub async fn connect(url: &Url, session_auth_token: Option<String>) -> anyhow::Result<Self> {
+    pub async fn connect(
+        url: &Url,
+        session_auth_token: Option<String>,
+        options: &SessionOptions,
+    ) -> anyhow::Result<Self> {
         let (tx, rx) = create_ws_transport(url).await?;
-        Self::create_from_transport(session_auth_token, tx, rx).await
+        Self::create_from_transport(session_auth_token, options, tx, rx).await
+    }
+
+    /// Join an existing session as an additional publisher
+    pub async fn join(
+        url: &Url,
+        session_id: String,
+        contribute_token: String,
+    ) -> anyhow::Result<Self> {
+        let (tx, rx) = create_ws_transport(url).await?;
+        Self::join_from_transport(session_id, contribute_token, tx, rx).await
     }
 
     pub async fn create_from_transport(
         session_auth_token: Option<String>,
+        options: &SessionOptions,
         tx: Sender<C2SMessage>,
         mut rx: Receiver<ClientEvent<S2CMessage>>,
     ) -> anyhow::Result<Self> {
         let _ = tx
-            .send(C2SMessage::InitializePublish { session_auth_token })
+            .send(C2SMessage::InitializePublish {
+                session_auth_token,
+                session_alias: options.session_alias.clone(),
+                discoverable: options.discoverable,
+            })
             .await;
 
-        let event = tokio::select! {
-            message = rx.recv() => message.context("unexpected client disconnect")?,
-            _ = time::sleep(Duration::from_secs(5)) => {
-                anyhow::bail!("session init timeout");
+        let result = match await_init_response(&mut rx).await? {
+            S2CMessage::ResponseInitializePublish {
+                session_id,
+                session_auth_token,
+                annotation_token,
+                contribute_token,
+                session_alias,
+            } => {
+                let mut result = Self::new(
+                    session_id,
+                    Some(session_auth_token),
+                    annotation_token,
+                    contribute_token,
+                    tx,
+                    rx,
+                );
+                result.session_alias = session_alias;
+                result
             }
+            response => anyhow::bail!("invalid response: {:?}", response),
         };
 
-        let (session_id, session_auth_token) = match event {
-            ClientEvent::RecvMessage(message) => match message {
-                S2CMessage::ResponseError { error } => {
-                    anyhow::bail!("server error: {}", error)
-                }
-                S2CMessage::ResponseInitializePublish {
-                    session_id,
-                    session_auth_token,
-                } => (session_id, session_auth_token),
-                S2CMessage::ResponseSessionInvalidId {} => {
-                    anyhow::bail!("session does not exists")
-                }
-                response => anyhow::bail!("invalid response: {:?}", response),
-            },
-            ClientEvent::RecvError(err) => anyhow::bail!("recv err: {:#}", err),
-            ClientEvent::SendError(err) => anyhow::bail!("send err: {:#}", err),
+        log::debug!("Connected with session id {}", result.session_id);
+        Ok(result)
+    }
+
+    pub async fn join_from_transport(
+        session_id: String,
+        contribute_token: String,
+        tx: Sender<C2SMessage>,
+        mut rx: Receiver<ClientEvent<S2CMessage>>,
+    ) -> anyhow::Result<Self> {
+        let _ = tx
+            .send(C2SMessage::InitializeContribute {
+                session_id: session_id.clone(),
+                contribute_token: contribute_token.clone(),
+            })
+            .await;
+
+        let annotation_token = match await_init_response(&mut rx).await? {
+            S2CMessage::ResponseInitializeContribute { annotation_token } => annotation_token,
+            response => anyhow::bail!("invalid response: {:?}", response),
         };
 
-        log::debug!("Connected with session id {}", session_id);
-        Ok(Self {
+        log::debug!("Joined session {}", session_id);
+        Ok(Self::new(
             session_id,
+            None,
+            annotation_token,
+            contribute_token,
+            tx,
+            rx,
+        ))
+    }
+
+    fn new(
+        session_id: String,
+        session_auth_token: Option<String>,
+        annotation_token: String,
+        contribute_token: String,
+        tx: Sender<C2SMessage>,
+        rx: Receiver<ClientEvent<S2CMessage>>,
+    ) -> Self {
+        Self {
+            session_id,
+            session_alias: None,
             session_auth_token,
+            annotation_token,
+            contribute_token,
+
+            annotations: Default::default(),
 
             generator: None,
             generate_interval: Box::pin(time::interval(Duration::from_millis(50))),
 
             transport_rx: rx,
             transport_tx: tx,
-        })
+        }
     }
 
     pub fn set_generator(&mut self, generator: Box<dyn RadarGenerator>) {
@@ -99,6 +213,30 @@ impl WebRadarPublisher {
         self.generator.take()
     }
 
+    /// Set the interval in which new radar states will be generated and published.
+    /// Defaults to 50ms.
+    pub fn set_generate_interval(&mut self, interval: Duration) {
+        self.generate_interval = Box::pin(time::interval(interval));
+    }
+
+    /// Currently active annotations of the session.
+    /// The `expires_in` value of every annotation will be adjusted to the remaining lifetime.
+    pub fn annotations(&self) -> impl Iterator<Item = RadarAnnotation> + '_ {
+        let now = Instant::now();
+        self.annotations
+            .iter()
+            .filter(move |(expires_at, _)| *expires_at > now)
+            .map(move |(expires_at, annotation)| RadarAnnotation {
+                expires_in: (*expires_at - now).as_secs_f32(),
+                ..annotation.clone()
+            })
+    }
+
+    /// Place an annotation on the radar of all session participants
+    pub fn create_annotation(&self, annotation: RadarAnnotationKind) {
+        self.send_message(C2SMessage::CreateAnnotation { annotation });
+    }
+
     fn send_message(&self, message: C2SMessage) {
         let _ = self.transport_tx.try_send(message);
     }
@@ -152,6 +290,21 @@ impl WebRadarPublisher {
                 log::debug!("Send error: {}", err);
                 Err(err)
             }
+            ClientEvent::RecvMessage(S2CMessage::NotifyAnnotation { annotation }) => {
+                let now = Instant::now();
+                self.annotations.retain(|(expires_at, _)| *expires_at > now);
+
+                let expires_at = now + Duration::from_secs_f32(annotation.expires_in.max(0.0));
+                self.annotations.push((expires_at, annotation));
+                Ok(())
+            }
+            ClientEvent::RecvMessage(S2CMessage::NotifySessionClosed {}) => {
+                anyhow::bail!("session has been closed")
+            }
+            ClientEvent::RecvMessage(S2CMessage::ResponseAnnotationRejected { reason }) => {
+                log::debug!("Annotation rejected: {}", reason);
+                Ok(())
+            }
             ClientEvent::RecvMessage(_message) => {
                 /* TODO? */
                 Ok(())
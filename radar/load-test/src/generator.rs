@@ -0,0 +1,120 @@
+use std::{
+    f32::consts::TAU,
+    sync::Arc,
+};
+
+use radar_client::RadarGenerator;
+use radar_shared::{
+    RadarPlayerPawn,
+    RadarState,
+};
+
+use crate::report::LatencyRecorder;
+
+/// Entity id of the first sequence marker pawn (see [SyntheticRadarGenerator]).
+/// Real entity ids never exceed 0x7FFF, hence the marker pawns never collide with them.
+const SEQUENCE_PAWN_ENTITY_ID: u32 = 0x1000_0000;
+
+/// Entity id of the sequence marker pawn of the target publisher
+fn sequence_pawn_entity_id(publisher_index: usize) -> u32 {
+    SEQUENCE_PAWN_ENTITY_ID + publisher_index as u32
+}
+
+/// Read the sequence number of the target publisher from the radar state
+pub fn read_sequence(state: &RadarState, publisher_index: usize) -> Option<u32> {
+    let entity_id = sequence_pawn_entity_id(publisher_index);
+    state
+        .player_pawns
+        .iter()
+        .find(|pawn| pawn.pawn_entity_id == entity_id)?
+        .player_name
+        .parse()
+        .ok()
+}
+
+/// Generates deterministic radar states with players moving in a circle.
+///
+/// The sequence number of each state is transmitted as the name of an additional marker pawn
+/// with an entity id unique to the publisher. The server merges pawns by their entity id
+/// and does not interpret them otherwise, so the sequence survives merging states of multiple
+/// contributors. This allows the subscribers to calculate the end-to-end latency and detect dropped states.
+pub struct SyntheticRadarGenerator {
+    publisher_index: usize,
+    player_count: usize,
+    sequence: u32,
+
+    recorder: Arc<LatencyRecorder>,
+}
+
+impl SyntheticRadarGenerator {
+    pub fn new(
+        publisher_index: usize,
+        player_count: usize,
+        recorder: Arc<LatencyRecorder>,
+    ) -> Self {
+        Self {
+            publisher_index,
+            player_count,
+            sequence: 0,
+
+            recorder,
+        }
+    }
+}
+
+impl RadarGenerator for SyntheticRadarGenerator {
+    fn generate_state(&mut self) -> anyhow::Result<RadarState> {
+        self.sequence = self.sequence.wrapping_add(1);
+
+        let angle_offset = self.sequence as f32 * 0.05;
+        let mut player_pawns = (0..self.player_count)
+            .map(|index| {
+                let angle = angle_offset + index as f32 * TAU / self.player_count as f32;
+                RadarPlayerPawn {
+                    controller_entity_id: Some(index as u32 + 1),
+                    pawn_entity_id: index as u32 + 100,
+                    team_id: if index % 2 == 0 { 2 } else { 3 },
+
+                    player_name: format!("Player {}", index),
+                    player_health: 100,
+                    player_has_defuser: false,
+                    player_flashtime: 0.0,
+
+                    weapon: 7,
+
+                    position: [angle.cos() * 1000.0, angle.sin() * 1000.0, 0.0],
+                    rotation: angle.to_degrees(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        player_pawns.push(RadarPlayerPawn {
+            controller_entity_id: None,
+            pawn_entity_id: sequence_pawn_entity_id(self.publisher_index),
+            team_id: 0,
+
+            player_name: self.sequence.to_string(),
+            player_health: 0,
+            player_has_defuser: false,
+            player_flashtime: 0.0,
+
+            weapon: 0,
+
+            position: [0.0; 3],
+            rotation: 0.0,
+        });
+
+        self.recorder
+            .record_sent(self.publisher_index, self.sequence);
+
+        Ok(RadarState {
+            world_name: "de_dust2".to_string(),
+            player_pawns,
+
+            planted_c4: None,
+            c4_entities: Vec::new(),
+
+            local_controller_entity_id: None,
+        })
+    }
+}
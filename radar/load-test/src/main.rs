@@ -0,0 +1,307 @@
+use std::{
+    net::ToSocketAddrs,
+    sync::{
+        atomic::Ordering,
+        Arc,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Context;
+use clap::Parser;
+use radar_client::{
+    create_ws_transport,
+    WebRadarPublisher,
+};
+use radar_server::{
+    create_local_transport,
+    HttpServeDirectory,
+    RadarServer,
+};
+use radar_shared::protocol::{
+    C2SMessage,
+    ClientEvent,
+    S2CMessage,
+};
+use tokio::{
+    sync::{
+        mpsc::{
+            Receiver,
+            Sender,
+        },
+        RwLock,
+    },
+    time,
+};
+use url::Url;
+
+use crate::{
+    generator::SyntheticRadarGenerator,
+    report::{
+        LatencyRecorder,
+        ProcessUsage,
+    },
+};
+
+mod generator;
+mod report;
+
+/// Valthrun radar server load test
+#[derive(Parser, Debug)]
+#[command(long_about = None)]
+struct Args {
+    /// Number of simulated publishers.
+    /// Every publisher creates its own session.
+    #[arg(short, long, default_value_t = 10)]
+    publishers: usize,
+
+    /// Number of simulated subscribers per session
+    #[arg(short, long, default_value_t = 5)]
+    subscribers: usize,
+
+    /// Number of synthetic players contained in every radar state
+    #[arg(long, default_value_t = 10)]
+    players: usize,
+
+    /// Interval (in milliseconds) in which every publisher sends a new radar state
+    #[arg(long, default_value_t = 50)]
+    publish_interval: u64,
+
+    /// Test duration in seconds
+    #[arg(short, long, default_value_t = 30)]
+    duration: u64,
+
+    /// Drive the radar server directly without any websockets in between.
+    /// This is usefull for deterministic CI runs.
+    #[arg(long)]
+    in_process: bool,
+
+    /// Address the local radar server should listen onto.
+    /// Not used when running in process.
+    #[arg(long, default_value = "127.0.0.1:0")]
+    address: String,
+}
+
+enum Transport {
+    InProcess,
+    WebSocket { base_url: Url },
+}
+
+impl Transport {
+    async fn connect(
+        &self,
+        server: &Arc<RwLock<RadarServer>>,
+        path: &str,
+    ) -> anyhow::Result<(Sender<C2SMessage>, Receiver<ClientEvent<S2CMessage>>)> {
+        match self {
+            Self::InProcess => create_local_transport(server).await,
+            Self::WebSocket { base_url } => create_ws_transport(&base_url.join(path)?).await,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Warn)
+        .parse_default_env()
+        .init();
+
+    let args = Args::parse();
+
+    let server = RadarServer::new();
+    let transport = if args.in_process {
+        Transport::InProcess
+    } else {
+        let address = server
+            .write()
+            .await
+            .listen_http(
+                args.address
+                    .to_socket_addrs()?
+                    .next()
+                    .context("invalid bind address")?,
+                HttpServeDirectory::None,
+            )
+            .await?;
+
+        Transport::WebSocket {
+            base_url: Url::parse(&format!("ws://{}/", address))?,
+        }
+    };
+
+    let recorder = Arc::new(LatencyRecorder::new(args.publishers));
+    let usage_start = ProcessUsage::current();
+    let test_start = Instant::now();
+    let deadline = test_start + Duration::from_secs(args.duration);
+
+    let mut tasks = Vec::with_capacity(args.publishers * (args.subscribers + 1));
+    for publisher_index in 0..args.publishers {
+        let (tx, rx) = transport
+            .connect(&server, "publish")
+            .await
+            .context("publisher connect")?;
+
+        let mut publisher =
+            WebRadarPublisher::create_from_transport(None, &Default::default(), tx, rx).await?;
+        publisher.set_generate_interval(Duration::from_millis(args.publish_interval));
+        publisher.set_generator(Box::new(SyntheticRadarGenerator::new(
+            publisher_index,
+            args.players,
+            recorder.clone(),
+        )));
+
+        for _ in 0..args.subscribers {
+            let (tx, rx) = transport
+                .connect(&server, "subscribe")
+                .await
+                .context("subscriber connect")?;
+
+            tasks.push(tokio::spawn(run_subscriber(
+                publisher_index,
+                publisher.session_id.clone(),
+                tx,
+                rx,
+                recorder.clone(),
+                deadline,
+            )));
+        }
+
+        tasks.push(tokio::spawn(run_publisher(publisher, deadline)));
+    }
+
+    println!(
+        "Running load test with {} publishers and {} subscribers per session ({})",
+        args.publishers,
+        args.subscribers,
+        if args.in_process {
+            "in process"
+        } else {
+            "websocket"
+        }
+    );
+
+    let mut failed_clients = 0;
+    for task in tasks {
+        if let Err(error) = task.await? {
+            log::warn!("Simulated client failed: {:#}", error);
+            failed_clients += 1;
+        }
+    }
+
+    let elapsed = test_start.elapsed();
+    let usage_end = ProcessUsage::current();
+
+    let (server_broadcasted, server_dropped) = {
+        let server = server.read().await;
+        let statistics = server.statistics();
+        (
+            statistics.messages_broadcasted.load(Ordering::Relaxed),
+            statistics.messages_dropped.load(Ordering::Relaxed),
+        )
+    };
+
+    let states_sent = recorder.states_sent();
+    let states_expected = states_sent * args.subscribers;
+    println!("Duration: {:.2?}", elapsed);
+    println!("Failed clients: {}", failed_clients);
+    println!(
+        "States published: {} ({:.1}/s)",
+        states_sent,
+        states_sent as f64 / elapsed.as_secs_f64()
+    );
+    println!(
+        "States received: {} of {} ({:.1}/s)",
+        recorder.states_received(),
+        states_expected,
+        recorder.states_received() as f64 / elapsed.as_secs_f64()
+    );
+    println!("States missed by subscribers: {}", recorder.states_missed());
+    println!(
+        "Server broadcasts: {} queued, {} dropped (try_send failures)",
+        server_broadcasted, server_dropped
+    );
+
+    match recorder.latency_percentiles() {
+        Some(latency) => println!("Latency: {}", latency),
+        None => println!("Latency: no samples"),
+    }
+
+    match (usage_start, usage_end) {
+        (Some(start), Some(end)) => {
+            let cpu_time = end.cpu_time.saturating_sub(start.cpu_time);
+            println!(
+                "CPU: {:.2?} ({:.1}% of one core)",
+                cpu_time,
+                cpu_time.as_secs_f64() / elapsed.as_secs_f64() * 100.0
+            );
+            println!(
+                "Memory: {:.1} MiB resident, {:.1} MiB peak",
+                end.memory_resident as f64 / (1024.0 * 1024.0),
+                end.memory_peak as f64 / (1024.0 * 1024.0)
+            );
+        }
+        _ => println!("CPU / Memory: unavailable (only supported on Linux)"),
+    }
+
+    Ok(())
+}
+
+async fn run_publisher(mut publisher: WebRadarPublisher, deadline: Instant) -> anyhow::Result<()> {
+    let result = time::timeout_at(deadline.into(), publisher.execute()).await;
+    publisher.close_connection().await;
+
+    match result {
+        Ok(result) => result,
+        Err(_) => {
+            /* test finished */
+            Ok(())
+        }
+    }
+}
+
+async fn run_subscriber(
+    publisher_index: usize,
+    session_id: String,
+    tx: Sender<C2SMessage>,
+    mut rx: Receiver<ClientEvent<S2CMessage>>,
+    recorder: Arc<LatencyRecorder>,
+    deadline: Instant,
+) -> anyhow::Result<()> {
+    tx.send(C2SMessage::InitializeSubscribe {
+        session_id,
+        annotation_token: None,
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("transport closed"))?;
+
+    let mut last_sequence = None;
+    loop {
+        let event = tokio::select! {
+            event = rx.recv() => event.context("transport closed unexpectetly")?,
+            _ = time::sleep_until(deadline.into()) => break,
+        };
+
+        match event {
+            ClientEvent::RecvMessage(S2CMessage::NotifyRadarState { state, .. }) => {
+                recorder.record_received(publisher_index, &state, &mut last_sequence);
+            }
+            ClientEvent::RecvMessage(S2CMessage::ResponseSessionInvalidId {}) => {
+                anyhow::bail!("session does not exists")
+            }
+            ClientEvent::RecvMessage(_) => {}
+            ClientEvent::RecvError(err) => return Err(err.context("recv error")),
+            ClientEvent::SendError(err) => return Err(err.context("send error")),
+        }
+    }
+
+    let _ = tx
+        .send(C2SMessage::Disconnect {
+            reason: "load test finished".to_string(),
+        })
+        .await;
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use radar_shared::RadarState;
+
+use crate::generator::read_sequence;
+
+/// Time after which an unacknowledged send timestamp will be discarded
+const SENT_RETENTION: Duration = Duration::from_secs(10);
+
+pub struct LatencyRecorder {
+    /// Send timestamp of every state by publisher and sequence number
+    sent: Vec<Mutex<BTreeMap<u32, Instant>>>,
+    samples: Mutex<Vec<Duration>>,
+
+    states_sent: AtomicUsize,
+    states_received: AtomicUsize,
+    states_missed: AtomicUsize,
+}
+
+impl LatencyRecorder {
+    pub fn new(publisher_count: usize) -> Self {
+        Self {
+            sent: (0..publisher_count).map(|_| Default::default()).collect(),
+            samples: Default::default(),
+
+            states_sent: Default::default(),
+            states_received: Default::default(),
+            states_missed: Default::default(),
+        }
+    }
+
+    pub fn record_sent(&self, publisher_index: usize, sequence: u32) {
+        let mut sent = self.sent[publisher_index].lock().unwrap();
+        while let Some((_, timestamp)) = sent.first_key_value() {
+            if timestamp.elapsed() < SENT_RETENTION {
+                break;
+            }
+
+            sent.pop_first();
+        }
+
+        sent.insert(sequence, Instant::now());
+        self.states_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a received state.
+    /// `last_sequence` must be tracked by the subscriber and is used to detect gaps.
+    pub fn record_received(
+        &self,
+        publisher_index: usize,
+        state: &RadarState,
+        last_sequence: &mut Option<u32>,
+    ) {
+        let Some(sequence) = read_sequence(state, publisher_index) else {
+            log::warn!("Received radar state without a sequence number");
+            return;
+        };
+
+        self.states_received.fetch_add(1, Ordering::Relaxed);
+        if let Some(last_sequence) = *last_sequence {
+            let missed = sequence.wrapping_sub(last_sequence).saturating_sub(1);
+            self.states_missed
+                .fetch_add(missed as usize, Ordering::Relaxed);
+        }
+        *last_sequence = Some(sequence);
+
+        let timestamp = self.sent[publisher_index]
+            .lock()
+            .unwrap()
+            .get(&sequence)
+            .cloned();
+
+        if let Some(timestamp) = timestamp {
+            self.samples.lock().unwrap().push(timestamp.elapsed());
+        }
+    }
+
+    pub fn states_sent(&self) -> usize {
+        self.states_sent.load(Ordering::Relaxed)
+    }
+
+    pub fn states_received(&self) -> usize {
+        self.states_received.load(Ordering::Relaxed)
+    }
+
+    pub fn states_missed(&self) -> usize {
+        self.states_missed.load(Ordering::Relaxed)
+    }
+
+    pub fn latency_percentiles(&self) -> Option<LatencyPercentiles> {
+        let mut samples = self.samples.lock().unwrap().clone();
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort();
+        let percentile = |value: f64| {
+            let index = ((samples.len() - 1) as f64 * value).round() as usize;
+            samples[index]
+        };
+
+        Some(LatencyPercentiles {
+            samples: samples.len(),
+            p50: percentile(0.50),
+            p90: percentile(0.90),
+            p99: percentile(0.99),
+            max: *samples.last().unwrap(),
+        })
+    }
+}
+
+pub struct LatencyPercentiles {
+    pub samples: usize,
+
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl fmt::Display for LatencyPercentiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "p50 {:.2?}, p90 {:.2?}, p99 {:.2?}, max {:.2?} ({} samples)",
+            self.p50, self.p90, self.p99, self.max, self.samples
+        )
+    }
+}
+
+/// CPU and memory usage of the current process.
+/// Note: The simulated clients run within the same process as the server.
+/// Only available on Linux as it's read from `/proc`.
+#[derive(Debug, Clone, Copy)]
+pub struct ProcessUsage {
+    /// Total user and system CPU time
+    pub cpu_time: Duration,
+
+    /// Current resident set size in bytes
+    pub memory_resident: u64,
+
+    /// Peak resident set size in bytes
+    pub memory_peak: u64,
+}
+
+impl ProcessUsage {
+    #[cfg(target_os = "linux")]
+    pub fn current() -> Option<Self> {
+        /* /proc reports CPU times in USER_HZ which is 100 on all relevant platforms */
+        const USER_HZ: u64 = 100;
+
+        let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+        /* the process name might contain spaces, hence skip everything until the closing bracket */
+        let mut fields = stat.rsplit_once(')')?.1.split_whitespace();
+        let utime = fields.nth(11)?.parse::<u64>().ok()?;
+        let stime = fields.next()?.parse::<u64>().ok()?;
+
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let read_status_kb = |name: &str| -> Option<u64> {
+            let line = status.lines().find(|line| line.starts_with(name))?;
+            let value = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+            Some(value * 1024)
+        };
+
+        Some(Self {
+            cpu_time: Duration::from_millis((utime + stime) * 1000 / USER_HZ),
+            memory_resident: read_status_kb("VmRSS:")?,
+            memory_peak: read_status_kb("VmHWM:")?,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn current() -> Option<Self> {
+        None
+    }
+}
@@ -22,6 +22,11 @@ struct Args {
     /// Static HTML file directory (optional)
     #[arg(long)]
     static_dir: Option<PathBuf>,
+
+    /// Enable the public session listing (GET /sessions).
+    /// Only sessions which opted in to be discoverable will be listed.
+    #[arg(long)]
+    session_listing: bool,
 }
 
 // $env:RUST_LOG="trace,tungstenite=info,tokio_tungstenite=info,tokio_util=info,rustls=info"
@@ -37,6 +42,7 @@ async fn main() -> anyhow::Result<()> {
     let server = RadarServer::new();
     {
         let mut server = server.write().await;
+        server.set_session_listing(args.session_listing);
 
         server
             .listen_http(
@@ -1,6 +1,10 @@
 use std::{
     net::SocketAddr,
     sync::Weak,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use anyhow::Context;
@@ -35,8 +39,56 @@ pub type ClientId = u32;
 #[derive(Clone)]
 pub enum ClientState {
     Uninitialized,
-    Publisher { session_id: String },
-    Subscriber { session_id: String },
+    Publisher {
+        session_id: String,
+    },
+    Contributor {
+        session_id: String,
+    },
+    Subscriber {
+        session_id: String,
+        can_annotate: bool,
+    },
+}
+
+/// Limits the amount of actions within a fixed time window
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+
+    window_start: Instant,
+    window_count: u32,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+
+            window_start: Instant::now(),
+            window_count: 0,
+        }
+    }
+
+    /// Returns false if the action exceeds the rate limit
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&mut self, now: Instant) -> bool {
+        if now.saturating_duration_since(self.window_start) > self.window {
+            self.window_start = now;
+            self.window_count = 0;
+        }
+
+        if self.window_count >= self.limit {
+            return false;
+        }
+
+        self.window_count += 1;
+        true
+    }
 }
 
 pub struct PubClient {
@@ -44,6 +96,7 @@ pub struct PubClient {
     pub address: SocketAddr,
 
     pub state: ClientState,
+    pub annotation_rate_limit: RateLimiter,
 
     pub tx: Sender<S2CMessage>,
 }
@@ -55,6 +108,8 @@ impl PubClient {
             address,
 
             state: ClientState::Uninitialized,
+            annotation_rate_limit: RateLimiter::new(10, Duration::from_secs(5)),
+
             tx,
         }
     }
@@ -246,3 +301,28 @@ impl PubClient {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::RateLimiter;
+
+    #[test]
+    fn test_rate_limit_window() {
+        let mut limiter = RateLimiter::new(2, Duration::from_secs(5));
+        let start = limiter.window_start;
+
+        assert!(limiter.try_acquire_at(start));
+        assert!(limiter.try_acquire_at(start + Duration::from_secs(1)));
+        assert!(!limiter.try_acquire_at(start + Duration::from_secs(2)));
+
+        /* the window ends after its full duration */
+        assert!(!limiter.try_acquire_at(start + Duration::from_secs(5)));
+
+        let next_window = start + Duration::from_millis(5_001);
+        assert!(limiter.try_acquire_at(next_window));
+        assert!(limiter.try_acquire_at(next_window));
+        assert!(!limiter.try_acquire_at(next_window));
+    }
+}
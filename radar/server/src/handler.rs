@@ -7,9 +7,12 @@ use radar_shared::protocol::{
 use tokio::sync::RwLock;
 
 use crate::{
+    normalize_session_alias,
     ClientState,
     PubClient,
-    PubSessionOwner,
+    PubSessionAnnotateResult,
+    PubSessionJoinResult,
+    PubSessionPublishResult,
     PubSessionSubscribeResult,
     RadarServer,
 };
@@ -23,7 +26,23 @@ pub struct ServerCommandHandler {
 impl ServerCommandHandler {
     pub async fn handle_command(&self, command: C2SMessage) -> S2CMessage {
         match command {
-            C2SMessage::InitializePublish { session_auth_token } => {
+            C2SMessage::InitializePublish {
+                session_auth_token,
+                session_alias,
+                discoverable,
+            } => {
+                let session_alias = match session_alias {
+                    Some(alias) => match normalize_session_alias(&alias) {
+                        Some(alias) => Some(alias),
+                        None => {
+                            return S2CMessage::ResponseError {
+                                error: "invalid session alias".to_string(),
+                            }
+                        }
+                    },
+                    None => None,
+                };
+
                 let mut server = self.server.write().await;
 
                 let session = if let Some(auth_token) = session_auth_token {
@@ -36,7 +55,10 @@ impl ServerCommandHandler {
 
                     session
                 } else {
-                    let Some(session) = server.pub_session_create(self.client_id).await else {
+                    let Some(session) = server
+                        .pub_session_create(self.client_id, session_alias.as_deref(), discoverable)
+                        .await
+                    else {
                         return S2CMessage::ResponseInvalidClientState {};
                     };
 
@@ -46,12 +68,44 @@ impl ServerCommandHandler {
                 S2CMessage::ResponseInitializePublish {
                     session_id: session.session_id.clone(),
                     session_auth_token: session.session_auth_token.clone(),
+                    annotation_token: session.annotation_token.clone(),
+                    contribute_token: session.contribute_token.clone(),
+                    session_alias: session.session_alias.clone(),
                 }
             }
-            C2SMessage::InitializeSubscribe { session_id, .. } => {
+            C2SMessage::InitializeContribute {
+                session_id,
+                contribute_token,
+            } => {
                 let mut server = self.server.write().await;
                 match server
-                    .pub_session_subscribe(&session_id, self.client_id)
+                    .pub_session_join(&session_id, self.client_id, &contribute_token)
+                    .await
+                {
+                    PubSessionJoinResult::Success { annotation_token } => {
+                        S2CMessage::ResponseInitializeContribute { annotation_token }
+                    }
+                    PubSessionJoinResult::InvalidClientId => {
+                        S2CMessage::ResponseInvalidClientState {}
+                    }
+                    PubSessionJoinResult::InvalidClientState => {
+                        S2CMessage::ResponseInvalidClientState {}
+                    }
+                    PubSessionJoinResult::InvalidSessionId => {
+                        S2CMessage::ResponseSessionInvalidId {}
+                    }
+                    PubSessionJoinResult::InvalidToken => S2CMessage::ResponseError {
+                        error: "invalid contribute token".to_string(),
+                    },
+                }
+            }
+            C2SMessage::InitializeSubscribe {
+                session_id,
+                annotation_token,
+            } => {
+                let mut server = self.server.write().await;
+                match server
+                    .pub_session_subscribe(&session_id, self.client_id, annotation_token.as_deref())
                     .await
                 {
                     PubSessionSubscribeResult::Success => S2CMessage::ResponseSubscribeSuccess {},
@@ -67,34 +121,68 @@ impl ServerCommandHandler {
                 }
             }
             C2SMessage::NotifyRadarState { state } => {
-                let server = self.server.read().await;
-                let client = self.client.read().await;
-
-                let session_id = match &client.state {
-                    ClientState::Publisher { session_id } => session_id,
-                    _ => return S2CMessage::ResponseInvalidClientState {},
+                let session_id = {
+                    let client = self.client.read().await;
+                    match &client.state {
+                        ClientState::Publisher { session_id } => session_id.clone(),
+                        ClientState::Contributor { session_id } => session_id.clone(),
+                        _ => return S2CMessage::ResponseInvalidClientState {},
+                    }
                 };
 
-                let session = match server.pub_session_find(session_id) {
-                    Some(session) => session,
-                    None => return S2CMessage::ResponseSessionInvalidId {},
-                };
+                let mut server = self.server.write().await;
+                match server.pub_session_publish(&session_id, self.client_id, state) {
+                    PubSessionPublishResult::Success => S2CMessage::ResponseSuccess {},
+                    PubSessionPublishResult::InvalidSessionId => {
+                        S2CMessage::ResponseSessionInvalidId {}
+                    }
+                    PubSessionPublishResult::NotAllowed => S2CMessage::ResponseError {
+                        error: "you're not allowed to send updates".to_string(),
+                    },
+                }
+            }
+            C2SMessage::CreateAnnotation { annotation } => {
+                let session_id = {
+                    let mut client = self.client.write().await;
+                    let session_id = match &client.state {
+                        ClientState::Publisher { session_id } => session_id.clone(),
+                        ClientState::Contributor { session_id } => session_id.clone(),
+                        ClientState::Subscriber {
+                            session_id,
+                            can_annotate: true,
+                        } => session_id.clone(),
+                        ClientState::Subscriber { .. } => {
+                            return S2CMessage::ResponseAnnotationRejected {
+                                reason: "missing annotation permission".to_string(),
+                            }
+                        }
+                        _ => return S2CMessage::ResponseInvalidClientState {},
+                    };
 
-                let PubSessionOwner::Owned {
-                    client_id: owner_client_id,
-                } = &session.owner
-                else {
-                    return S2CMessage::ResponseSessionInvalidId {};
+                    if !client.annotation_rate_limit.try_acquire() {
+                        return S2CMessage::ResponseAnnotationRejected {
+                            reason: "rate limit exceeded".to_string(),
+                        };
+                    }
+
+                    session_id
                 };
 
-                if *owner_client_id != client.client_id {
-                    return S2CMessage::ResponseError {
-                        error: "you're not allowed to send updates".to_string(),
-                    };
+                let mut server = self.server.write().await;
+                match server
+                    .pub_session_annotate(&session_id, self.client_id, annotation)
+                    .await
+                {
+                    PubSessionAnnotateResult::Success => S2CMessage::ResponseSuccess {},
+                    PubSessionAnnotateResult::InvalidSessionId => {
+                        S2CMessage::ResponseSessionInvalidId {}
+                    }
+                    PubSessionAnnotateResult::InvalidAnnotation(reason) => {
+                        S2CMessage::ResponseAnnotationRejected {
+                            reason: reason.to_string(),
+                        }
+                    }
                 }
-
-                session.broadcast(&S2CMessage::NotifyRadarState { state });
-                S2CMessage::ResponseSuccess {}
             }
             C2SMessage::Disconnect { .. } => {
                 /* command is already handled within the connection code */
@@ -5,3 +5,6 @@ mod server;
 pub use server::*;
 
 mod handler;
+
+mod local;
+pub use local::*;
@@ -0,0 +1,87 @@
+use std::{
+    net::{
+        Ipv4Addr,
+        SocketAddr,
+    },
+    sync::Arc,
+};
+
+use radar_shared::protocol::{
+    C2SMessage,
+    ClientEvent,
+    S2CMessage,
+};
+use tokio::sync::{
+    mpsc::{
+        self,
+        Receiver,
+        Sender,
+    },
+    RwLock,
+};
+
+use crate::{
+    PubClient,
+    RadarServer,
+};
+
+/// Register a client which is connected to the server within the same process.
+/// Messages are passed as they are without any websocket / serialisation in between.
+///
+/// The returned channels are equivalent to the channels created by the websocket transport
+/// of the radar client and can be used as a drop in replacement.
+pub async fn create_local_transport(
+    server: &Arc<RwLock<RadarServer>>,
+) -> anyhow::Result<(Sender<C2SMessage>, Receiver<ClientEvent<S2CMessage>>)> {
+    let (message_tx, mut message_tx_rx) = mpsc::channel(16);
+    let (message_rx_tx, message_rx) = mpsc::channel(16);
+
+    {
+        let mut server = server.write().await;
+        let client_fut = server
+            .register_client(
+                PubClient::new(message_tx, SocketAddr::from((Ipv4Addr::LOCALHOST, 0))),
+                message_rx,
+            )
+            .await;
+
+        tokio::spawn(client_fut);
+    }
+
+    let (channel_tx, mut channel_tx_rx) = mpsc::channel::<C2SMessage>(16);
+    let (channel_rx_tx, channel_rx) = mpsc::channel(16);
+
+    tokio::spawn(async move {
+        while let Some(message) = channel_tx_rx.recv().await {
+            if message_rx_tx
+                .send(ClientEvent::RecvMessage(message))
+                .await
+                .is_err()
+            {
+                /* server side client handler has been closed */
+                return;
+            }
+        }
+
+        /* channel locally closed */
+        let _ = message_rx_tx
+            .send(ClientEvent::RecvError(anyhow::anyhow!(
+                "client disconnected"
+            )))
+            .await;
+    });
+
+    tokio::spawn(async move {
+        while let Some(message) = message_tx_rx.recv().await {
+            if channel_rx_tx
+                .send(ClientEvent::RecvMessage(message))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    Ok((channel_tx, channel_rx))
+}
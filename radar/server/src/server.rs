@@ -1,8 +1,15 @@
 use std::{
-    collections::BTreeMap,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
     net::SocketAddr,
     path::PathBuf,
     sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
         Arc,
         Weak,
     },
@@ -13,10 +20,17 @@ use std::{
 };
 
 use futures_util::Future;
-use radar_shared::protocol::{
-    C2SMessage,
-    ClientEvent,
-    S2CMessage,
+use radar_shared::{
+    protocol::{
+        C2SMessage,
+        ClientEvent,
+        S2CMessage,
+        SessionListing,
+    },
+    RadarAnnotation,
+    RadarAnnotationKind,
+    RadarContributor,
+    RadarState,
 };
 use rand::{
     distributions::Alphanumeric,
@@ -52,25 +66,253 @@ pub enum PubSessionOwner {
     Unbound { timestamp: Instant },
 }
 
+/// Server wide message counters
+#[derive(Debug, Default)]
+pub struct ServerStatistics {
+    /// Messages successfully queued for a session subscriber
+    pub messages_broadcasted: AtomicUsize,
+
+    /// Messages dropped because the subscribers send queue was full or closed
+    pub messages_dropped: AtomicUsize,
+}
+
+/// Time after which a contributions state will no longer be considered when merging
+const CONTRIBUTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct SessionContribution {
+    timestamp: Instant,
+    state: RadarState,
+}
+
+struct ActiveAnnotation {
+    expires_at: Instant,
+    annotation: RadarAnnotation,
+}
+
 pub struct PubSession {
     pub owner: PubSessionOwner,
 
     pub session_id: String,
     pub session_auth_token: String,
+    pub annotation_token: String,
+    pub contribute_token: String,
+
+    pub session_alias: Option<String>,
+    pub discoverable: bool,
+
+    /// Map name and player count of the last published state
+    world_name: Option<String>,
+    player_count: usize,
 
     subscriber: BTreeMap<u32, mpsc::Sender<S2CMessage>>,
+    statistics: Arc<ServerStatistics>,
+
+    /// Additional publishers (beside the owner) of this session
+    contributors: BTreeSet<ClientId>,
+    contributions: BTreeMap<ClientId, SessionContribution>,
+
+    annotations: Vec<ActiveAnnotation>,
+    annotation_id_counter: u32,
 }
 
 impl PubSession {
     pub fn broadcast(&self, message: &S2CMessage) {
         for subscriber in self.subscriber.values() {
-            let _ = subscriber.try_send(message.clone());
+            if subscriber.try_send(message.clone()).is_ok() {
+                self.statistics
+                    .messages_broadcasted
+                    .fetch_add(1, Ordering::Relaxed);
+            } else {
+                self.statistics
+                    .messages_dropped
+                    .fetch_add(1, Ordering::Relaxed);
+            }
         }
     }
 
     pub fn subscriber_count(&self) -> usize {
         self.subscriber.len()
     }
+
+    fn update_summary(&mut self, state: &RadarState) {
+        self.world_name = Some(state.world_name.clone());
+        self.player_count = state.player_pawns.len();
+    }
+
+    pub fn listing(&self) -> SessionListing {
+        SessionListing {
+            session_id: self.session_id.clone(),
+            session_alias: self.session_alias.clone(),
+
+            world_name: self.world_name.clone(),
+
+            player_count: self.player_count,
+            viewer_count: self.subscriber.len(),
+        }
+    }
+
+    pub fn contributor_count(&self) -> usize {
+        self.contributors.len()
+    }
+
+    fn is_publisher(&self, client_id: ClientId) -> bool {
+        if self.contributors.contains(&client_id) {
+            return true;
+        }
+
+        matches!(&self.owner, PubSessionOwner::Owned { client_id: owner_id } if *owner_id == client_id)
+    }
+
+    fn merge_contributions(&self) -> Option<(RadarState, Vec<RadarContributor>)> {
+        let owner_id = match &self.owner {
+            PubSessionOwner::Owned { client_id } => Some(*client_id),
+            PubSessionOwner::Unbound { .. } => None,
+        };
+
+        merge_contributions(&self.contributions, owner_id, Instant::now())
+    }
+
+    /// Currently active annotations with their remaining lifetime
+    pub fn annotations(&self) -> impl Iterator<Item = RadarAnnotation> + '_ {
+        let now = Instant::now();
+        self.annotations
+            .iter()
+            .filter(move |entry| entry.expires_at > now)
+            .map(move |entry| RadarAnnotation {
+                expires_in: (entry.expires_at - now).as_secs_f32(),
+                ..entry.annotation.clone()
+            })
+    }
+
+    /// Remove all annotations which expired at the given time
+    fn prune_annotations(&mut self, now: Instant) {
+        self.annotations
+            .retain(|annotation| annotation.expires_at > now);
+    }
+}
+
+/// Merge the states of all publishers into one state.
+/// For every entity and the planted C4 the data of the most recent state will be used.
+/// The local controller is always taken from the session owner (if contributing).
+fn merge_contributions(
+    contributions: &BTreeMap<ClientId, SessionContribution>,
+    owner_id: Option<ClientId>,
+    now: Instant,
+) -> Option<(RadarState, Vec<RadarContributor>)> {
+    let mut contributions = contributions
+        .iter()
+        .filter(|(_, contribution)| {
+            now.duration_since(contribution.timestamp) < CONTRIBUTION_TIMEOUT
+        })
+        .collect::<Vec<_>>();
+    contributions.sort_by_key(|(_, contribution)| contribution.timestamp);
+
+    let (_, latest) = contributions.last()?;
+    let mut world_name = &latest.state.world_name;
+    let mut player_pawns = BTreeMap::new();
+    let mut planted_c4 = None;
+    let mut c4_entities = BTreeMap::new();
+    let mut local_controller_entity_id = latest.state.local_controller_entity_id;
+    let mut contributors = Vec::with_capacity(contributions.len());
+
+    for (client_id, contribution) in contributions.iter() {
+        /* newer states override the entities of older states */
+        if !contribution.state.world_name.is_empty() {
+            world_name = &contribution.state.world_name;
+        }
+
+        for pawn in contribution.state.player_pawns.iter() {
+            player_pawns.insert(pawn.pawn_entity_id, pawn.clone());
+        }
+
+        if let Some(c4) = &contribution.state.planted_c4 {
+            /* not every contributor might see the planted bomb */
+            planted_c4 = Some(c4.clone());
+        }
+
+        for c4 in contribution.state.c4_entities.iter() {
+            c4_entities.insert(c4.entity_id, c4.clone());
+        }
+
+        let session_owner = owner_id == Some(**client_id);
+        if session_owner {
+            local_controller_entity_id = contribution.state.local_controller_entity_id;
+        }
+
+        contributors.push(RadarContributor {
+            contributor_id: **client_id,
+            session_owner,
+            local_controller_entity_id: contribution.state.local_controller_entity_id,
+            state_age: now.duration_since(contribution.timestamp).as_secs_f32(),
+        });
+    }
+
+    let state = RadarState {
+        world_name: world_name.clone(),
+        player_pawns: player_pawns.into_values().collect(),
+
+        planted_c4,
+        c4_entities: c4_entities.into_values().collect(),
+
+        local_controller_entity_id,
+    };
+    Some((state, contributors))
+}
+
+/// Normalize the requested session alias.
+/// Returns `None` if the alias is invalid.
+pub fn normalize_session_alias(alias: &str) -> Option<String> {
+    let alias = alias.trim().to_lowercase();
+    if alias.len() < 3 || alias.len() > 24 {
+        return None;
+    }
+
+    if alias.starts_with('-')
+        || alias.ends_with('-')
+        || !alias
+            .chars()
+            .all(|value| value.is_ascii_alphanumeric() || value == '-')
+    {
+        return None;
+    }
+
+    Some(alias)
+}
+
+/// Validate the annotation and return the annotations lifetime
+fn validate_annotation(annotation: &RadarAnnotationKind) -> Result<Duration, &'static str> {
+    let position_valid = |position: &[f32; 3]| position.iter().all(|value| value.is_finite());
+    match annotation {
+        RadarAnnotationKind::Ping { position } => {
+            if !position_valid(position) {
+                return Err("invalid position");
+            }
+
+            Ok(Duration::from_secs(4))
+        }
+        RadarAnnotationKind::Marker { position, label } => {
+            if !position_valid(position) {
+                return Err("invalid position");
+            }
+
+            if label.as_ref().map_or(false, |label| label.len() > 32) {
+                return Err("label too long");
+            }
+
+            Ok(Duration::from_secs(60))
+        }
+        RadarAnnotationKind::Line { points } => {
+            if points.len() < 2 || points.len() > 64 {
+                return Err("a line must contain between 2 and 64 points");
+            }
+
+            if !points.iter().all(position_valid) {
+                return Err("invalid position");
+            }
+
+            Ok(Duration::from_secs(10))
+        }
+    }
 }
 
 pub enum HttpServeDirectory {
@@ -92,6 +334,11 @@ pub struct RadarServer {
 
     clients: BTreeMap<u32, Arc<RwLock<PubClient>>>,
     pub_sessions: BTreeMap<String, PubSession>,
+    statistics: Arc<ServerStatistics>,
+
+    /// Session alias to session id mapping
+    session_aliases: BTreeMap<String, String>,
+    session_listing: bool,
 
     www_acceptor: Option<JoinHandle<()>>,
 }
@@ -104,6 +351,10 @@ impl RadarServer {
 
             clients: Default::default(),
             pub_sessions: Default::default(),
+            statistics: Default::default(),
+
+            session_aliases: Default::default(),
+            session_listing: false,
 
             www_acceptor: None,
         };
@@ -116,6 +367,40 @@ impl RadarServer {
         })
     }
 
+    pub fn statistics(&self) -> &ServerStatistics {
+        &self.statistics
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn session_count(&self) -> usize {
+        self.pub_sessions.len()
+    }
+
+    /// Enable the public session listing (`GET /sessions`).
+    /// Only sessions which opted in to be discoverable will be listed.
+    pub fn set_session_listing(&mut self, enabled: bool) {
+        self.session_listing = enabled;
+    }
+
+    /// List all discoverable sessions.
+    /// Returns `None` if the session listing has been disabled.
+    pub fn session_listing(&self) -> Option<Vec<SessionListing>> {
+        if !self.session_listing {
+            return None;
+        }
+
+        Some(
+            self.pub_sessions
+                .values()
+                .filter(|session| session.discoverable)
+                .map(PubSession::listing)
+                .collect(),
+        )
+    }
+
     async fn tick_task(this: Weak<RwLock<Self>>) {
         let mut interval = time::interval(Duration::from_secs(1));
         loop {
@@ -147,13 +432,18 @@ impl RadarServer {
             log::info!("Session {} expired. Closing session.", &session_id);
             self.pub_session_close(&session_id).await;
         }
+
+        let now = Instant::now();
+        for session in self.pub_sessions.values_mut() {
+            session.prune_annotations(now);
+        }
     }
 
     pub async fn listen_http(
         &mut self,
         addr: impl Into<SocketAddr>,
         static_serve: HttpServeDirectory,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<SocketAddr> {
         if self.www_acceptor.is_some() {
             anyhow::bail!("www already started");
         }
@@ -172,6 +462,31 @@ impl RadarServer {
             })
             .boxed();
 
+        let server = self.ref_self.clone();
+        let sessions_route = warp::path("sessions")
+            .and(warp::path::end())
+            .and(warp::get())
+            .then(move || {
+                let server = server.clone();
+                async move {
+                    let listing = match server.upgrade() {
+                        Some(server) => server.read().await.session_listing(),
+                        None => None,
+                    };
+
+                    let reply: Box<dyn warp::Reply> = match listing {
+                        Some(listing) => Box::new(warp::reply::json(&listing)),
+                        None => Box::new(warp::http::StatusCode::NOT_FOUND),
+                    };
+                    reply
+                }
+            });
+
+        let ws_route = ws_route
+            .or(sessions_route)
+            .map(|reply| -> Box<dyn warp::Reply> { Box::new(reply) })
+            .boxed();
+
         let routes: warp::filters::BoxedFilter<(Box<dyn warp::Reply>,)> = match static_serve {
             HttpServeDirectory::Disk { path } => ws_route
                 .or(warp::fs::dir(path.clone()))
@@ -191,7 +506,7 @@ impl RadarServer {
 
         log::info!("Started server on {}", address);
 
-        Ok(())
+        Ok(address)
     }
 
     pub async fn unregister_client(&mut self, client_id: u32, clean_disconnect: bool) {
@@ -212,7 +527,10 @@ impl RadarServer {
                     self.pub_session_unbound(&session_id).await;
                 }
             }
-            ClientState::Subscriber { session_id } => {
+            ClientState::Contributor { session_id } => {
+                self.pub_session_leave(&session_id, client_id).await;
+            }
+            ClientState::Subscriber { session_id, .. } => {
                 self.pub_session_unsubscribe(&session_id, client_id).await;
             }
             ClientState::Uninitialized => { /* Nothing to do! */ }
@@ -282,7 +600,45 @@ impl RadarServer {
         }
     }
 
-    pub async fn pub_session_create(&mut self, owner_id: ClientId) -> Option<&PubSession> {
+    /// Find the session by its id or alias
+    fn pub_session_resolve_id(&self, session_id: &str) -> Option<String> {
+        if self.pub_sessions.contains_key(session_id) {
+            return Some(session_id.to_string());
+        }
+
+        self.session_aliases
+            .get(&session_id.to_lowercase())
+            .cloned()
+    }
+
+    /// Allocate the session alias.
+    /// On collision a numeric suffix will be appended.
+    fn allocate_session_alias(&mut self, alias: &str, session_id: &str) -> Option<String> {
+        let alias_free = |this: &Self, alias: &str| {
+            !this.session_aliases.contains_key(alias) && !this.pub_sessions.contains_key(alias)
+        };
+
+        let alias = if alias_free(self, alias) {
+            alias.to_string()
+        } else {
+            (2..100)
+                .map(|suffix| format!("{}-{}", alias, suffix))
+                .find(|alias| alias_free(self, alias))?
+        };
+
+        self.session_aliases
+            .insert(alias.clone(), session_id.to_string());
+        Some(alias)
+    }
+
+    /// Create a new session.
+    /// The session alias must be normalized via `normalize_session_alias`.
+    pub async fn pub_session_create(
+        &mut self,
+        owner_id: ClientId,
+        session_alias: Option<&str>,
+        discoverable: bool,
+    ) -> Option<&PubSession> {
         let owner = match self.clients.get(&owner_id) {
             Some(client) => client,
             None => return None,
@@ -305,6 +661,23 @@ impl RadarServer {
             .take(12)
             .collect::<String>();
 
+        let annotation_token = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .map(char::from)
+            .take(8)
+            .collect::<String>();
+
+        let contribute_token = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .map(char::from)
+            .take(12)
+            .collect::<String>();
+
+        let session_alias = match session_alias {
+            Some(alias) => self.allocate_session_alias(alias, &session_id),
+            None => None,
+        };
+
         self.pub_sessions.insert(
             session_id.clone(),
             PubSession {
@@ -314,8 +687,23 @@ impl RadarServer {
 
                 session_id: session_id.clone(),
                 session_auth_token: session_auth_token.clone(),
+                annotation_token,
+                contribute_token,
+
+                session_alias,
+                discoverable,
+
+                world_name: None,
+                player_count: 0,
 
                 subscriber: Default::default(),
+                statistics: self.statistics.clone(),
+
+                contributors: Default::default(),
+                contributions: Default::default(),
+
+                annotations: Default::default(),
+                annotation_id_counter: 0,
             },
         );
 
@@ -382,6 +770,10 @@ impl RadarServer {
             None => return,
         };
 
+        if let Some(alias) = &session.session_alias {
+            self.session_aliases.remove(alias);
+        }
+
         log::info!("Session {} closed", session_id);
         session.broadcast(&S2CMessage::NotifySessionClosed {});
 
@@ -394,6 +786,121 @@ impl RadarServer {
             let mut client = client.write().await;
             client.state = ClientState::Uninitialized;
         }
+
+        for client_id in session.contributors.iter() {
+            let client = match self.clients.get(client_id) {
+                Some(client) => client,
+                None => continue,
+            };
+
+            let mut client = client.write().await;
+            client.send_command(S2CMessage::NotifySessionClosed {});
+            client.state = ClientState::Uninitialized;
+        }
+    }
+
+    /// Join a session as additional publisher
+    pub async fn pub_session_join(
+        &mut self,
+        session_id: &str,
+        client_id: ClientId,
+        contribute_token: &str,
+    ) -> PubSessionJoinResult {
+        let client = match self.clients.get(&client_id) {
+            Some(client) => client,
+            None => return PubSessionJoinResult::InvalidClientId,
+        };
+
+        let mut client = client.write().await;
+        if !matches!(client.state, ClientState::Uninitialized) {
+            return PubSessionJoinResult::InvalidClientState;
+        }
+
+        let Some(session_id) = self.pub_session_resolve_id(session_id) else {
+            return PubSessionJoinResult::InvalidSessionId;
+        };
+
+        let session = match self.pub_sessions.get_mut(&session_id) {
+            Some(session) => session,
+            None => return PubSessionJoinResult::InvalidSessionId,
+        };
+
+        if session.contribute_token != contribute_token {
+            return PubSessionJoinResult::InvalidToken;
+        }
+
+        session.contributors.insert(client_id);
+        log::info!("Client {} joined session {}", client_id, session_id);
+
+        client.state = ClientState::Contributor {
+            session_id: session.session_id.clone(),
+        };
+        PubSessionJoinResult::Success {
+            annotation_token: session.annotation_token.clone(),
+        }
+    }
+
+    /// Remove an additional publisher from the session
+    pub async fn pub_session_leave(&mut self, session_id: &str, client_id: ClientId) {
+        if let Some(session) = self.pub_sessions.get_mut(session_id) {
+            session.contributors.remove(&client_id);
+            session.contributions.remove(&client_id);
+        }
+
+        if let Some(client) = self.clients.get(&client_id) {
+            let mut client = client.write().await;
+            if matches!(&client.state, ClientState::Contributor { session_id: client_session_id } if client_session_id == session_id)
+            {
+                client.state = ClientState::Uninitialized;
+            }
+        }
+    }
+
+    /// Update the radar state of a session publisher and
+    /// broadcast the merged state of all publishers to the subscribers.
+    pub fn pub_session_publish(
+        &mut self,
+        session_id: &str,
+        client_id: ClientId,
+        state: RadarState,
+    ) -> PubSessionPublishResult {
+        let session = match self.pub_sessions.get_mut(session_id) {
+            Some(session) => session,
+            None => return PubSessionPublishResult::InvalidSessionId,
+        };
+
+        if !session.is_publisher(client_id) {
+            return PubSessionPublishResult::NotAllowed;
+        }
+
+        if session.contributors.is_empty() {
+            /* single publisher session, nothing to merge */
+            session.contributions.clear();
+            session.update_summary(&state);
+            session.broadcast(&S2CMessage::NotifyRadarState {
+                state,
+                contributors: Vec::new(),
+            });
+            return PubSessionPublishResult::Success;
+        }
+
+        session.contributions.insert(
+            client_id,
+            SessionContribution {
+                timestamp: Instant::now(),
+                state,
+            },
+        );
+
+        if let Some((state, contributors)) = session.merge_contributions() {
+            session.update_summary(&state);
+            session.broadcast(&S2CMessage::NotifyRadarState {
+                state,
+                contributors,
+            });
+        }
+
+        PubSessionPublishResult::Success
     }
 
     pub fn pub_session_find(&self, session_id: &str) -> Option<&PubSession> {
@@ -412,6 +919,7 @@ impl RadarServer {
             let mut client = client.write().await;
             if let ClientState::Subscriber {
                 session_id: client_session_id,
+                ..
             } = &client.state
             {
                 if client_session_id == session_id {
@@ -429,6 +937,7 @@ impl RadarServer {
         &mut self,
         session_id: &String,
         client_id: u32,
+        annotation_token: Option<&str>,
     ) -> PubSessionSubscribeResult {
         let client = match self.clients.get(&client_id) {
             Some(client) => client,
@@ -440,7 +949,11 @@ impl RadarServer {
             return PubSessionSubscribeResult::InvalidClientState;
         }
 
-        let session = match self.pub_sessions.get_mut(session_id) {
+        let Some(session_id) = self.pub_session_resolve_id(session_id) else {
+            return PubSessionSubscribeResult::InvalidSessionId;
+        };
+
+        let session = match self.pub_sessions.get_mut(&session_id) {
             Some(session) => session,
             None => return PubSessionSubscribeResult::InvalidSessionId,
         };
@@ -453,11 +966,63 @@ impl RadarServer {
             viewers: session.subscriber.len(),
         });
 
+        for annotation in session.annotations() {
+            client.send_command(S2CMessage::NotifyAnnotation { annotation });
+        }
+
         client.state = ClientState::Subscriber {
             session_id: session.session_id.clone(),
+            can_annotate: annotation_token.map_or(false, |token| token == session.annotation_token),
         };
         PubSessionSubscribeResult::Success
     }
+
+    /// Add an annotation to the session and broadcast it to
+    /// all subscribers as well as the session publishers.
+    pub async fn pub_session_annotate(
+        &mut self,
+        session_id: &str,
+        author_id: ClientId,
+        annotation: RadarAnnotationKind,
+    ) -> PubSessionAnnotateResult {
+        let lifetime = match validate_annotation(&annotation) {
+            Ok(lifetime) => lifetime,
+            Err(reason) => return PubSessionAnnotateResult::InvalidAnnotation(reason),
+        };
+
+        let session = match self.pub_sessions.get_mut(session_id) {
+            Some(session) => session,
+            None => return PubSessionAnnotateResult::InvalidSessionId,
+        };
+
+        session.annotation_id_counter = session.annotation_id_counter.wrapping_add(1);
+        let annotation = RadarAnnotation {
+            annotation_id: session.annotation_id_counter,
+            author_id,
+            expires_in: lifetime.as_secs_f32(),
+            kind: annotation,
+        };
+        session.annotations.push(ActiveAnnotation {
+            expires_at: Instant::now() + lifetime,
+            annotation: annotation.clone(),
+        });
+
+        let message = S2CMessage::NotifyAnnotation { annotation };
+        session.broadcast(&message);
+
+        let mut publishers = session.contributors.iter().cloned().collect::<Vec<_>>();
+        if let PubSessionOwner::Owned { client_id } = &session.owner {
+            publishers.push(*client_id);
+        }
+
+        for client_id in publishers {
+            if let Some(publisher) = self.clients.get(&client_id) {
+                publisher.read().await.send_command(message.clone());
+            }
+        }
+
+        PubSessionAnnotateResult::Success
+    }
 }
 
 pub enum PubSessionSubscribeResult {
@@ -466,3 +1031,447 @@ pub enum PubSessionSubscribeResult {
     InvalidSessionId,
     InvalidClientId,
 }
+
+pub enum PubSessionJoinResult {
+    Success { annotation_token: String },
+    InvalidClientState,
+    InvalidSessionId,
+    InvalidClientId,
+    InvalidToken,
+}
+
+pub enum PubSessionPublishResult {
+    Success,
+    InvalidSessionId,
+    NotAllowed,
+}
+
+pub enum PubSessionAnnotateResult {
+    Success,
+    InvalidSessionId,
+    InvalidAnnotation(&'static str),
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::BTreeMap,
+        sync::Arc,
+        time::{
+            Duration,
+            Instant,
+        },
+    };
+
+    use radar_shared::{
+        protocol::{
+            C2SMessage,
+            S2CMessage,
+        },
+        PlantedC4State,
+        RadarAnnotationKind,
+        RadarPlantedC4,
+        RadarPlayerPawn,
+        RadarState,
+    };
+    use tokio::sync::{
+        mpsc,
+        RwLock,
+    };
+
+    use super::{
+        merge_contributions,
+        validate_annotation,
+        RadarServer,
+        SessionContribution,
+        CONTRIBUTION_TIMEOUT,
+    };
+    use crate::{
+        handler::ServerCommandHandler,
+        PubClient,
+    };
+
+    fn pawn(pawn_entity_id: u32, player_health: i32) -> RadarPlayerPawn {
+        RadarPlayerPawn {
+            controller_entity_id: None,
+            pawn_entity_id,
+            team_id: 2,
+
+            player_name: format!("Player {}", pawn_entity_id),
+            player_health,
+            player_has_defuser: false,
+            player_flashtime: 0.0,
+
+            weapon: 0,
+
+            position: [0.0; 3],
+            rotation: 0.0,
+        }
+    }
+
+    fn contribution(
+        timestamp: Instant,
+        local_controller_entity_id: u32,
+        player_pawns: Vec<RadarPlayerPawn>,
+        planted_c4: Option<RadarPlantedC4>,
+    ) -> SessionContribution {
+        SessionContribution {
+            timestamp,
+            state: RadarState {
+                world_name: "de_dust2".to_string(),
+                player_pawns,
+
+                planted_c4,
+                c4_entities: Vec::new(),
+
+                local_controller_entity_id: Some(local_controller_entity_id),
+            },
+        }
+    }
+
+    fn planted_c4() -> RadarPlantedC4 {
+        RadarPlantedC4 {
+            position: [1.0, 2.0, 3.0],
+            bomb_site: 1,
+            state: PlantedC4State::Detonated {},
+        }
+    }
+
+    #[test]
+    fn test_merge_by_freshness() {
+        let base = Instant::now();
+        let mut contributions = BTreeMap::new();
+        contributions.insert(
+            1,
+            contribution(
+                base,
+                1,
+                vec![pawn(100, 50), pawn(101, 100)],
+                Some(planted_c4()),
+            ),
+        );
+        contributions.insert(
+            2,
+            contribution(base + Duration::from_secs(1), 2, vec![pawn(100, 20)], None),
+        );
+
+        let (state, contributors) =
+            merge_contributions(&contributions, None, base + Duration::from_secs(2)).unwrap();
+        assert_eq!(contributors.len(), 2);
+        assert_eq!(state.player_pawns.len(), 2);
+        assert_eq!(state.player_pawns[0].player_health, 20);
+        assert_eq!(state.player_pawns[1].player_health, 100);
+
+        /* the planted C4 is only known by the older contribution */
+        assert_eq!(state.planted_c4.map(|c4| c4.bomb_site), Some(1));
+        assert_eq!(state.local_controller_entity_id, Some(2));
+    }
+
+    #[test]
+    fn test_merge_timeout() {
+        let base = Instant::now();
+        let mut contributions = BTreeMap::new();
+        contributions.insert(
+            1,
+            contribution(base, 1, vec![pawn(100, 50)], Some(planted_c4())),
+        );
+        contributions.insert(
+            2,
+            contribution(base + CONTRIBUTION_TIMEOUT, 2, vec![pawn(101, 100)], None),
+        );
+
+        let now = base + CONTRIBUTION_TIMEOUT + Duration::from_secs(1);
+        let (state, contributors) = merge_contributions(&contributions, None, now).unwrap();
+        assert_eq!(contributors.len(), 1);
+        assert_eq!(contributors[0].contributor_id, 2);
+        assert_eq!(state.player_pawns.len(), 1);
+        assert_eq!(state.player_pawns[0].pawn_entity_id, 101);
+        assert!(state.planted_c4.is_none());
+
+        let now = base + CONTRIBUTION_TIMEOUT * 3;
+        assert!(merge_contributions(&contributions, None, now).is_none());
+    }
+
+    #[test]
+    fn test_merge_owner_local_controller() {
+        let base = Instant::now();
+        let mut contributions = BTreeMap::new();
+        contributions.insert(1, contribution(base, 5, vec![pawn(100, 50)], None));
+        contributions.insert(
+            2,
+            contribution(base + Duration::from_secs(1), 7, vec![pawn(101, 100)], None),
+        );
+
+        let now = base + Duration::from_secs(2);
+        let (state, contributors) = merge_contributions(&contributions, Some(1), now).unwrap();
+        assert_eq!(state.local_controller_entity_id, Some(5));
+        assert!(contributors
+            .iter()
+            .any(|contributor| contributor.contributor_id == 1 && contributor.session_owner));
+        assert!(contributors
+            .iter()
+            .any(|contributor| contributor.contributor_id == 2 && !contributor.session_owner));
+
+        /* without an owner the most recent contribution is used */
+        let (state, _) = merge_contributions(&contributions, None, now).unwrap();
+        assert_eq!(state.local_controller_entity_id, Some(7));
+    }
+
+    /// Register a new client and return its command handler
+    async fn connect_client(
+        server: &Arc<RwLock<RadarServer>>,
+    ) -> (ServerCommandHandler, mpsc::Receiver<S2CMessage>) {
+        let (tx, rx) = mpsc::channel(16);
+        let (_event_tx, event_rx) = mpsc::channel(16);
+
+        let mut server_guard = server.write().await;
+        /* the client event loop is not needed as commands are handled directly */
+        let _ = server_guard
+            .register_client(PubClient::new(tx, "127.0.0.1:0".parse().unwrap()), event_rx)
+            .await;
+
+        let client_id = server_guard.client_id_counter;
+        let client = server_guard.clients[&client_id].clone();
+        drop(server_guard);
+
+        (
+            ServerCommandHandler {
+                server: server.clone(),
+                client,
+                client_id,
+            },
+            rx,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_join_and_subscribe_by_alias() {
+        let server = RadarServer::new();
+
+        let (owner, _owner_rx) = connect_client(&server).await;
+        let S2CMessage::ResponseInitializePublish {
+            session_id,
+            annotation_token,
+            contribute_token,
+            session_alias,
+            ..
+        } = owner
+            .handle_command(C2SMessage::InitializePublish {
+                session_auth_token: None,
+                session_alias: Some("Team-Alpha".to_string()),
+                discoverable: false,
+            })
+            .await
+        else {
+            panic!("expected a publish response");
+        };
+        assert_eq!(session_alias.as_deref(), Some("team-alpha"));
+
+        let (contributor, _contributor_rx) = connect_client(&server).await;
+        match contributor
+            .handle_command(C2SMessage::InitializeContribute {
+                session_id: "team-alpha".to_string(),
+                contribute_token,
+            })
+            .await
+        {
+            S2CMessage::ResponseInitializeContribute {
+                annotation_token: contribute_annotation_token,
+            } => assert_eq!(contribute_annotation_token, annotation_token),
+            _ => panic!("expected a contribute response"),
+        }
+
+        /* the contributor must be bound to the actual session id */
+        assert!(server
+            .read()
+            .await
+            .pub_session_find(&session_id)
+            .unwrap()
+            .is_publisher(contributor.client_id));
+        assert!(matches!(
+            contributor
+                .handle_command(C2SMessage::NotifyRadarState {
+                    state: contribution(Instant::now(), 1, Vec::new(), None).state,
+                })
+                .await,
+            S2CMessage::ResponseSuccess {}
+        ));
+
+        let (subscriber, _subscriber_rx) = connect_client(&server).await;
+        assert!(matches!(
+            subscriber
+                .handle_command(C2SMessage::InitializeSubscribe {
+                    session_id: "TEAM-ALPHA".to_string(),
+                    annotation_token: None,
+                })
+                .await,
+            S2CMessage::ResponseSubscribeSuccess {}
+        ));
+        assert_eq!(
+            server
+                .read()
+                .await
+                .pub_session_find(&session_id)
+                .unwrap()
+                .subscriber_count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_validate_annotation() {
+        let position = [1.0, 2.0, 3.0];
+        assert_eq!(
+            validate_annotation(&RadarAnnotationKind::Ping { position }),
+            Ok(Duration::from_secs(4))
+        );
+        assert_eq!(
+            validate_annotation(&RadarAnnotationKind::Ping {
+                position: [f32::NAN, 0.0, 0.0]
+            }),
+            Err("invalid position")
+        );
+        assert_eq!(
+            validate_annotation(&RadarAnnotationKind::Marker {
+                position: [0.0, f32::INFINITY, 0.0],
+                label: None,
+            }),
+            Err("invalid position")
+        );
+
+        let marker = |label_length: usize| RadarAnnotationKind::Marker {
+            position,
+            label: Some("x".repeat(label_length)),
+        };
+        assert_eq!(
+            validate_annotation(&marker(32)),
+            Ok(Duration::from_secs(60))
+        );
+        assert_eq!(validate_annotation(&marker(33)), Err("label too long"));
+
+        let line = |point_count: usize| RadarAnnotationKind::Line {
+            points: vec![position; point_count],
+        };
+        assert!(validate_annotation(&line(1)).is_err());
+        assert_eq!(validate_annotation(&line(2)), Ok(Duration::from_secs(10)));
+        assert!(validate_annotation(&line(64)).is_ok());
+        assert!(validate_annotation(&line(65)).is_err());
+        assert_eq!(
+            validate_annotation(&RadarAnnotationKind::Line {
+                points: vec![position, [0.0, 0.0, f32::NEG_INFINITY]],
+            }),
+            Err("invalid position")
+        );
+    }
+
+    /// Create a new session and return the owner alongside the session id and annotation token
+    async fn create_session(
+        server: &Arc<RwLock<RadarServer>>,
+    ) -> (
+        ServerCommandHandler,
+        mpsc::Receiver<S2CMessage>,
+        String,
+        String,
+    ) {
+        let (owner, owner_rx) = connect_client(server).await;
+        let S2CMessage::ResponseInitializePublish {
+            session_id,
+            annotation_token,
+            ..
+        } = owner
+            .handle_command(C2SMessage::InitializePublish {
+                session_auth_token: None,
+                session_alias: None,
+                discoverable: false,
+            })
+            .await
+        else {
+            panic!("expected a publish response");
+        };
+
+        (owner, owner_rx, session_id, annotation_token)
+    }
+
+    #[tokio::test]
+    async fn test_annotation_permission() {
+        let server = RadarServer::new();
+        let (_owner, mut owner_rx, session_id, annotation_token) = create_session(&server).await;
+
+        let ping = || C2SMessage::CreateAnnotation {
+            annotation: RadarAnnotationKind::Ping { position: [0.0; 3] },
+        };
+
+        for token in [None, Some("invalid".to_string())] {
+            let (subscriber, _subscriber_rx) = connect_client(&server).await;
+            assert!(matches!(
+                subscriber
+                    .handle_command(C2SMessage::InitializeSubscribe {
+                        session_id: session_id.clone(),
+                        annotation_token: token,
+                    })
+                    .await,
+                S2CMessage::ResponseSubscribeSuccess {}
+            ));
+
+            assert!(matches!(
+                subscriber.handle_command(ping()).await,
+                S2CMessage::ResponseAnnotationRejected { reason } if reason == "missing annotation permission"
+            ));
+        }
+
+        let (subscriber, _subscriber_rx) = connect_client(&server).await;
+        subscriber
+            .handle_command(C2SMessage::InitializeSubscribe {
+                session_id: session_id.clone(),
+                annotation_token: Some(annotation_token),
+            })
+            .await;
+        assert!(matches!(
+            subscriber.handle_command(ping()).await,
+            S2CMessage::ResponseSuccess {}
+        ));
+
+        /* the annotation will be relayed to the session owner */
+        let mut owner_notified = false;
+        while let Ok(message) = owner_rx.try_recv() {
+            owner_notified |= matches!(message, S2CMessage::NotifyAnnotation { annotation } if annotation.author_id == subscriber.client_id);
+        }
+        assert!(owner_notified);
+    }
+
+    #[tokio::test]
+    async fn test_annotation_expiry() {
+        let server = RadarServer::new();
+        let (owner, _owner_rx, session_id, _) = create_session(&server).await;
+
+        for annotation in [
+            RadarAnnotationKind::Ping { position: [0.0; 3] },
+            RadarAnnotationKind::Marker {
+                position: [0.0; 3],
+                label: None,
+            },
+        ] {
+            assert!(matches!(
+                owner
+                    .handle_command(C2SMessage::CreateAnnotation { annotation })
+                    .await,
+                S2CMessage::ResponseSuccess {}
+            ));
+        }
+
+        let mut server = server.write().await;
+        let session = server.pub_sessions.get_mut(&session_id).unwrap();
+        assert_eq!(session.annotations().count(), 2);
+
+        /* the ping expires after 4 seconds, the marker after 60 seconds */
+        session.prune_annotations(Instant::now() + Duration::from_secs(5));
+        assert_eq!(session.annotations.len(), 1);
+        assert!(matches!(
+            session.annotations[0].annotation.kind,
+            RadarAnnotationKind::Marker { .. }
+        ));
+
+        session.prune_annotations(Instant::now() + Duration::from_secs(61));
+        assert!(session.annotations.is_empty());
+    }
+}
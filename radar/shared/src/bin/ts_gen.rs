@@ -5,6 +5,7 @@ use radar_shared::protocol::{
     C2SMessage,
     HandshakeMessage,
     S2CMessage,
+    SessionListing,
 };
 use typescript_type_def::{
     write_definition_file_from_type_infos,
@@ -36,6 +37,7 @@ fn main() -> anyhow::Result<()> {
         &S2CMessage::INFO,
         &C2SMessage::INFO,
         &HandshakeMessage::INFO,
+        &SessionListing::INFO,
     ];
     write_definition_file_from_type_infos(&mut output, options, definitions)?;
 
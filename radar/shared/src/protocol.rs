@@ -4,9 +4,19 @@ use serde::{
 };
 use typescript_type_def::TypeDef;
 
-use crate::RadarState;
+use crate::{
+    RadarAnnotation,
+    RadarAnnotationKind,
+    RadarContributor,
+    RadarState,
+};
 
-pub const RADAR_PROTOCOL_VERSION: u32 = 2;
+/// Version of the radar protocol.
+/// Must be increased whenever a message changes in an incompatible way
+/// so that outdated clients receive a version mismatch instead of failing to parse a message.
+///
+/// Version 3: annotations, team sessions and session aliases
+pub const RADAR_PROTOCOL_VERSION: u32 = 3;
 
 #[derive(Serialize, Deserialize, Clone, Debug, TypeDef)]
 pub enum SubscribeResult {
@@ -28,17 +38,40 @@ pub enum S2CMessage {
     ResponseInitializePublish {
         session_id: String,
         session_auth_token: String,
+
+        /// Token which allows subscribers to annotate the radar
+        annotation_token: String,
+
+        /// Token which allows other publishers to contribute to this session
+        contribute_token: String,
+
+        /// Human readable alias which can be used instead of the session id
+        session_alias: Option<String>,
+    },
+    ResponseInitializeContribute {
+        annotation_token: String,
     },
     ResponseSubscribeSuccess {},
     ResponseSessionInvalidId {},
+    ResponseAnnotationRejected {
+        reason: String,
+    },
 
     NotifyRadarState {
         state: RadarState,
+
+        /// All publishers which contributed to this state.
+        /// Empty if the session only has one publisher.
+        #[serde(default)]
+        contributors: Vec<RadarContributor>,
     },
     NotifyViewCount {
         viewers: usize,
     },
     NotifySessionClosed {},
+    NotifyAnnotation {
+        annotation: RadarAnnotation,
+    },
 }
 
 #[derive(Serialize, Deserialize, TypeDef)]
@@ -47,20 +80,60 @@ pub enum C2SMessage {
     InitializePublish {
         #[serde(default)]
         session_auth_token: Option<String>,
+
+        /// Request a human readable alias for the session.
+        /// If the alias is already taken a numeric suffix will be appended.
+        #[serde(default)]
+        session_alias: Option<String>,
+
+        /// List the session in the public session listing
+        #[serde(default)]
+        discoverable: bool,
+    },
+    /// Join an existing session as an additional publisher.
+    /// The radar states of all publishers will be merged by the server.
+    InitializeContribute {
+        session_id: String,
+        contribute_token: String,
     },
     InitializeSubscribe {
         session_id: String,
+
+        /// Annotation token of the session.
+        /// Only subscribers with a valid token are allowed to annotate the radar.
+        #[serde(default)]
+        annotation_token: Option<String>,
     },
 
     NotifyRadarState {
         state: RadarState,
     },
 
+    /// Place an annotation (ping, marker or line) on the radar.
+    /// The annotation will be broadcasted to every session participant.
+    CreateAnnotation {
+        annotation: RadarAnnotationKind,
+    },
+
     Disconnect {
         reason: String,
     },
 }
 
+/// Entry of the public session listing (`GET /sessions`)
+#[derive(Serialize, Deserialize, Clone, Debug, TypeDef)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionListing {
+    pub session_id: String,
+    pub session_alias: Option<String>,
+
+    /// Current map name
+    pub world_name: Option<String>,
+
+    pub player_count: usize,
+    pub viewer_count: usize,
+}
+
 pub enum ClientEvent<T> {
     RecvMessage(T),
     RecvError(anyhow::Error),
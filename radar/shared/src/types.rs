@@ -91,3 +91,51 @@ pub struct RadarC4 {
     pub position: [f32; 3],
     pub owner_entity_id: Option<u32>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, TypeDef)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+pub enum RadarAnnotationKind {
+    /// Short lived ping at a specific location
+    Ping { position: [f32; 3] },
+
+    /// Marker at a specific location with an optional label
+    Marker {
+        position: [f32; 3],
+        label: Option<String>,
+    },
+
+    /// Line connecting all points
+    Line { points: Vec<[f32; 3]> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, TypeDef)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarAnnotation {
+    /// Unique id of the annotation within the session
+    pub annotation_id: u32,
+
+    /// Client id of the annotation author
+    pub author_id: u32,
+
+    /// Time (in seconds) until the annotation expires
+    pub expires_in: f32,
+
+    pub kind: RadarAnnotationKind,
+}
+
+/// A publisher contributing to a (team) session
+#[derive(Serialize, Deserialize, Clone, Debug, TypeDef)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarContributor {
+    /// Client id of the contributor
+    pub contributor_id: u32,
+
+    /// Whether the contributor is the session owner
+    pub session_owner: bool,
+
+    /// The contributors local controller entity id
+    pub local_controller_entity_id: Option<u32>,
+
+    /// Time (in seconds) since the last received state of the contributor
+    pub state_age: f32,
+}
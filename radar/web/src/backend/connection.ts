@@ -1,5 +1,12 @@
 import { EventEmitter } from "../utils/ee";
-import { C2SMessage, HandshakeProtocolV2, RadarState, S2CMessage } from "./definitions";
+import {
+    C2SMessage,
+    HandshakeProtocolV2,
+    RadarAnnotation,
+    RadarAnnotationKind,
+    RadarState,
+    S2CMessage,
+} from "./definitions";
 
 export type SubscriberClientState =
     | {
@@ -13,6 +20,7 @@ export type SubscriberClientState =
 export interface SubscriberClientEvents {
     state_changed: SubscriberClientState;
     "radar.state": RadarState;
+    "radar.annotation": RadarAnnotation;
 }
 
 export class SubscriberClient {
@@ -20,6 +28,7 @@ export class SubscriberClient {
 
     private currentState: SubscriberClientState;
     private connection: WebSocket | null;
+    private annotationToken: string | null;
 
     private commandHandler: { [T in S2CMessage["type"]]?: (payload: (S2CMessage & { type: T })["payload"]) => void } =
         {};
@@ -28,6 +37,7 @@ export class SubscriberClient {
         this.events = new EventEmitter();
         this.currentState = { state: "new" };
         this.connection = null;
+        this.annotationToken = null;
 
         this.commandHandler = {};
         this.commandHandler["response-error"] = (payload) => {
@@ -51,6 +61,14 @@ export class SubscriberClient {
         this.commandHandler["notify-session-closed"] = () => {
             this.updateState({ state: "disconnected" });
         };
+
+        this.commandHandler["notify-annotation"] = (payload) => {
+            this.events.emit("radar.annotation", payload.annotation);
+        };
+
+        this.commandHandler["response-annotation-rejected"] = (payload) => {
+            console.warn(`Annotation rejected: ${payload.reason}`);
+        };
     }
 
     public getState(): Readonly<SubscriberClientState> {
@@ -81,11 +99,28 @@ export class SubscriberClient {
         this.connection = null;
     }
 
-    public connect(sessionId: string) {
+    /**
+     * Only clients which connected with the annotation token of the session are allowed to annotate the radar.
+     */
+    public canAnnotate(): boolean {
+        return this.annotationToken !== null;
+    }
+
+    public createAnnotation(annotation: RadarAnnotationKind) {
+        if (this.currentState.state !== "connected") {
+            return;
+        }
+
+        this.sendCommand("create-annotation", { annotation });
+    }
+
+    public connect(sessionId: string, annotationToken: string | null = null) {
         if (this.currentState.state != "new") {
             throw new Error(`invalid session state`);
         }
 
+        this.annotationToken = annotationToken;
+
         this.updateState({ state: "connecting" });
         this.connection = new WebSocket(this.targetAddress);
         this.connection.onopen = () => {
@@ -94,7 +129,7 @@ export class SubscriberClient {
                 JSON.stringify({
                     type: "request-initialize",
                     payload: {
-                        clientVersion: 2,
+                        clientVersion: 3,
                     },
                 } satisfies HandshakeProtocolV2),
             );
@@ -130,6 +165,7 @@ export class SubscriberClient {
                         this.updateState({ state: "initializing" });
                         this.sendCommand("initialize-subscribe", {
                             session_id: sessionId,
+                            annotation_token: annotationToken,
                         });
                         break;
 
@@ -92,6 +92,73 @@ export type RadarState = {
     localControllerEntityId: U32 | null;
 };
 export type Usize = number;
+export type RadarAnnotationKind =
+    | ({
+          /**
+           * Short lived ping at a specific location
+           */
+          type: "ping";
+      } & {
+          position: [F32, F32, F32];
+      })
+    | ({
+          /**
+           * Marker at a specific location with an optional label
+           */
+          type: "marker";
+      } & {
+          position: [F32, F32, F32];
+          label: string | null;
+      })
+    | ({
+          /**
+           * Line connecting all points
+           */
+          type: "line";
+      } & {
+          points: [F32, F32, F32][];
+      });
+export type RadarAnnotation = {
+    /**
+     * Unique id of the annotation within the session
+     */
+    annotationId: U32;
+
+    /**
+     * Client id of the annotation author
+     */
+    authorId: U32;
+
+    /**
+     * Time (in seconds) until the annotation expires
+     */
+    expiresIn: F32;
+    kind: RadarAnnotationKind;
+};
+/**
+ * A publisher contributing to a (team) session
+ */
+export type RadarContributor = {
+    /**
+     * Client id of the contributor
+     */
+    contributorId: U32;
+
+    /**
+     * Whether the contributor is the session owner
+     */
+    sessionOwner: boolean;
+
+    /**
+     * The contributors local controller entity id
+     */
+    localControllerEntityId: U32 | null;
+
+    /**
+     * Time (in seconds) since the last received state of the contributor
+     */
+    stateAge: F32;
+};
 export type S2CMessage =
     | {
           type: "response-success";
@@ -111,6 +178,28 @@ export type S2CMessage =
           type: "response-initialize-publish";
           payload: {
               session_id: string;
+              session_auth_token: string;
+
+              /**
+               * Token which allows subscribers to annotate the radar
+               */
+              annotation_token: string;
+
+              /**
+               * Token which allows other publishers to contribute to this session
+               */
+              contribute_token: string;
+
+              /**
+               * Human readable alias which can be used instead of the session id
+               */
+              session_alias: string | null;
+          };
+      }
+    | {
+          type: "response-initialize-contribute";
+          payload: {
+              annotation_token: string;
           };
       }
     | {
@@ -121,10 +210,22 @@ export type S2CMessage =
           type: "response-session-invalid-id";
           payload: {};
       }
+    | {
+          type: "response-annotation-rejected";
+          payload: {
+              reason: string;
+          };
+      }
     | {
           type: "notify-radar-state";
           payload: {
               state: RadarState;
+
+              /**
+               * All publishers which contributed to this state.
+               * Empty if the session only has one publisher.
+               */
+              contributors?: RadarContributor[];
           };
       }
     | {
@@ -136,16 +237,53 @@ export type S2CMessage =
     | {
           type: "notify-session-closed";
           payload: {};
+      }
+    | {
+          type: "notify-annotation";
+          payload: {
+              annotation: RadarAnnotation;
+          };
       };
 export type C2SMessage =
     | {
           type: "initialize-publish";
-          payload: {};
+          payload: {
+              session_auth_token?: string | null;
+
+              /**
+               * Request a human readable alias for the session.
+               * If the alias is already taken a numeric suffix will be appended.
+               */
+              session_alias?: string | null;
+
+              /**
+               * List the session in the public session listing
+               */
+              discoverable?: boolean;
+          };
       }
+    | ({
+          /**
+           * Join an existing session as an additional publisher.
+           * The radar states of all publishers will be merged by the server.
+           */
+          type: "initialize-contribute";
+      } & {
+          payload: {
+              session_id: string;
+              contribute_token: string;
+          };
+      })
     | {
           type: "initialize-subscribe";
           payload: {
               session_id: string;
+
+              /**
+               * Annotation token of the session.
+               * Only subscribers with a valid token are allowed to annotate the radar.
+               */
+              annotation_token?: string | null;
           };
       }
     | {
@@ -154,6 +292,17 @@ export type C2SMessage =
               state: RadarState;
           };
       }
+    | ({
+          /**
+           * Place an annotation (ping, marker or line) on the radar.
+           * The annotation will be broadcasted to every session participant.
+           */
+          type: "create-annotation";
+      } & {
+          payload: {
+              annotation: RadarAnnotationKind;
+          };
+      })
     | {
           type: "disconnect";
           payload: {
@@ -202,3 +351,17 @@ export type HandshakeProtocolV2 =
           };
       };
 export type HandshakeMessage = HandshakeProtocolV1 | HandshakeProtocolV2;
+/**
+ * Entry of the public session listing (`GET /sessions`)
+ */
+export type SessionListing = {
+    sessionId: string;
+    sessionAlias: string | null;
+
+    /**
+     * Current map name
+     */
+    worldName: string | null;
+    playerCount: Usize;
+    viewerCount: Usize;
+};
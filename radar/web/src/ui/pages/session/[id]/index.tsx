@@ -1,14 +1,14 @@
 import { Settings as IconSettings } from "@mui/icons-material";
 import { Alert, Box, CircularProgress, IconButton, Typography } from "@mui/material";
 import * as React from "react";
-import { useParams } from "react-router-dom";
+import { useParams, useSearchParams } from "react-router-dom";
 import { kDefaultRadarState } from "../../../../backend/connection";
-import { RadarState } from "../../../../backend/definitions";
+import { RadarAnnotation, RadarState } from "../../../../backend/definitions";
 import { useAppDispatch } from "../../../../state";
 import { updateRadarSettings } from "../../../../state/radar-settings";
 import { SubscriberClientProvider, useSubscriberClient } from "../../../components/connection";
 import ModalSettings from "./modal-settings";
-import { ContextRadarState, RadarRenderer } from "./radar";
+import { ActiveAnnotation, ContextRadarAnnotations, ContextRadarState, RadarRenderer } from "./radar";
 
 const kServerUrl: string | null = process.env.SERVER_URL;
 export default React.memo(() => {
@@ -72,13 +72,14 @@ const ClientStateNew = React.memo(() => {
     const client = useSubscriberClient();
     const { state } = useSubscriberClientState();
     const { sessionId } = useParams() as any;
+    const [searchParams] = useSearchParams();
 
     React.useEffect(() => {
         if (state !== "new") {
             return;
         }
 
-        client.connect(sessionId);
+        client.connect(sessionId, searchParams.get("annotate"));
     }, [client, state]);
 
     if (state !== "new") {
@@ -137,8 +138,32 @@ const ClientStateConnected = React.memo(() => {
     const client = useSubscriberClient();
     const state = useSubscriberClientState();
     const [radarState, setRadarState] = React.useState<RadarState>(kDefaultRadarState);
+    const [annotations, setAnnotations] = React.useState<ActiveAnnotation[]>([]);
 
     React.useEffect(() => client.events.on("radar.state", (update) => setRadarState(update)), [client]);
+    React.useEffect(
+        () =>
+            client.events.on("radar.annotation", (annotation: RadarAnnotation) => {
+                const now = Date.now();
+                setAnnotations((annotations) => [
+                    ...annotations.filter((entry) => entry.expiresAt > now),
+                    { annotation, expiresAt: now + annotation.expiresIn * 1000 },
+                ]);
+            }),
+        [client],
+    );
+    React.useEffect(() => {
+        /* remove expired annotations */
+        const interval = setInterval(() => {
+            const now = Date.now();
+            setAnnotations((annotations) =>
+                annotations.some((entry) => entry.expiresAt <= now)
+                    ? annotations.filter((entry) => entry.expiresAt > now)
+                    : annotations,
+            );
+        }, 250);
+        return () => clearInterval(interval);
+    }, []);
 
     if (state.state !== "connected") {
         return;
@@ -156,7 +181,9 @@ const ClientStateConnected = React.memo(() => {
             }}
         >
             <ContextRadarState.Provider value={radarState}>
-                <RadarRenderer />
+                <ContextRadarAnnotations.Provider value={annotations}>
+                    <RadarRenderer />
+                </ContextRadarAnnotations.Provider>
             </ContextRadarState.Provider>
         </Box>
     );
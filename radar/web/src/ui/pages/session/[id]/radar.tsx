@@ -6,14 +6,25 @@ import { LoadedMap, loadMap, MapStyle } from "../../../../map-info";
 import ImageBomb from "../../../../assets/bomb.png";
 import { useAppSelector } from "../../../../state";
 import BombIndicator from "../../../components/bomb/bomb-indicator";
+import { useSubscriberClient } from "../../../components/connection";
 import IconPlayerDead from "./icon_player_dead.svg";
 import IconPlayer from "./icon_player.svg";
-import { F32, RadarPlayerPawn, RadarState } from "../../../../backend/definitions";
+import { F32, RadarAnnotation, RadarAnnotationKind, RadarPlayerPawn, RadarState } from "../../../../backend/definitions";
 import SizedContainer from "../../../components/container/sized-container";
 import SqareContainer, { useSqareSize } from "../../../components/container/sqare-container";
 import { useQuery } from "react-query";
 
+export type ActiveAnnotation = {
+    annotation: RadarAnnotation;
+
+    /**
+     * Timestamp (in milliseconds) when the annotation expires
+     */
+    expiresAt: number;
+};
+
 export const ContextRadarState = React.createContext<RadarState>(kDefaultRadarState);
+export const ContextRadarAnnotations = React.createContext<ActiveAnnotation[]>([]);
 const ContextMap = React.createContext<LoadedMap>(null);
 
 export const RadarRenderer = React.memo(() => {
@@ -170,21 +181,41 @@ const MapLevel = React.memo((props: { level: string }) => {
 
     const map = React.useContext(ContextMap);
     const { playerPawns, c4Entities, plantedC4 } = React.useContext(ContextRadarState);
+    const annotations = React.useContext(ContextRadarAnnotations);
     const { colorDotCT, colorDotT, colorDotOwn, mapStyle } = useAppSelector(state => state.radarSettings);
+    const client = useSubscriberClient();
 
     if (!map) {
         /* we need the map info */
         return null;
     }
 
+    const canAnnotate = client.canAnnotate();
+    const placeAnnotation = (event: React.MouseEvent<HTMLDivElement>) => {
+        if (!canAnnotate) {
+            return;
+        }
+
+        const bounds = event.currentTarget.getBoundingClientRect();
+        const position = getWorldPosition(map, level, [
+            (event.clientX - bounds.left) * 100 / bounds.width,
+            (event.clientY - bounds.top) * 100 / bounds.height,
+        ]);
+
+        /* shift click places a marker instead of a ping */
+        client.createAnnotation(event.shiftKey ? { type: "marker", position, label: null } : { type: "ping", position });
+    };
+
     const mapImage = map.mapStyles.find(style => style.name === mapStyle) ?? map.mapStyles[0] ?? null;
     return (
         <Box
+            onClick={placeAnnotation}
             sx={{
                 position: "relative",
 
                 height: "100%",
                 width: "100%",
+                cursor: canAnnotate ? "crosshair" : undefined,
 
                 ".icon_player_svg__view-cone": {
                     fill: "#fff",
@@ -218,6 +249,7 @@ const MapLevel = React.memo((props: { level: string }) => {
             {playerPawns.filter(pawn => getMapLevel(map, pawn.position) === level).map(pawn => <MapPlayerPawn playerInfo={pawn} key={`player-${pawn.pawnEntityId}`} />)}
             {c4Entities.filter(entity => getMapLevel(map, entity.position) === level).map(entity => <MapC4 position={entity.position} key={`c4-${entity.entityId}`} />)}
             {plantedC4 && getMapLevel(map, plantedC4.position) === level ? <MapC4 position={plantedC4.position} key="planted-c4" /> : null}
+            {annotations.filter(entry => getAnnotationLevel(map, entry.annotation.kind) === level).map(entry => <MapAnnotation annotation={entry.annotation} key={`annotation-${entry.annotation.annotationId}`} />)}
         </Box>
     );
 });
@@ -226,6 +258,36 @@ const getMapLevel = (map: LoadedMap, position: [F32, F32, F32]): string => {
     return map.verticalSections.find(section => section.altitudeMin <= position[2] && position[2] < section.altitudeMax)?.name ?? "default";
 }
 
+const getAnnotationLevel = (map: LoadedMap, kind: RadarAnnotationKind): string => {
+    const position = kind.type === "line" ? kind.points[0] : kind.position;
+    return position ? getMapLevel(map, position) : "default";
+}
+
+/**
+ * Convert a world position into map coordinates (in percent)
+ */
+const getMapPosition = (map: LoadedMap, position: [number, number, number]): [number, number] => {
+    const mapSize = map.scale * 1024;
+    return [
+        (position[0] - map.pos_x) * 100 / mapSize,
+        (position[1] - map.pos_y) * 100 / -mapSize
+    ];
+};
+
+/**
+ * Convert map coordinates (in percent) into a world position.
+ * The altitude will be the lower bound of the map level.
+ */
+const getWorldPosition = (map: LoadedMap, level: string, position: [number, number]): [F32, F32, F32] => {
+    const mapSize = map.scale * 1024;
+    const section = map.verticalSections.find(section => section.name === level);
+    return [
+        map.pos_x + position[0] * mapSize / 100,
+        map.pos_y - position[1] * mapSize / 100,
+        section?.altitudeMin ?? 0,
+    ];
+};
+
 const useMapPosition = (position: [number, number, number]): [number, number] | null => {
     const map = React.useContext(ContextMap);
     if (!map) {
@@ -233,11 +295,7 @@ const useMapPosition = (position: [number, number, number]): [number, number] |
         return null;
     }
 
-    const mapSize = map.scale * 1024;
-    return [
-        (position[0] - map.pos_x) * 100 / mapSize,
-        (position[1] - map.pos_y) * 100 / -mapSize
-    ];
+    return getMapPosition(map, position);
 };
 
 
@@ -324,3 +382,94 @@ const MapC4 = React.memo((props: { position: [number, number, number] }) => {
         />
     );
 });
+
+const kAnnotationColor = "#4caf50";
+
+const MapAnnotation = React.memo((props: { annotation: RadarAnnotation }) => {
+    const { kind } = props.annotation;
+    const map = React.useContext(ContextMap);
+    const iconSize = useAppSelector((state) => state.radarSettings.iconSize);
+    if (!map) {
+        return null;
+    }
+
+    if (kind.type === "line") {
+        return (
+            <svg
+                viewBox="0 0 100 100"
+                preserveAspectRatio="none"
+                style={{
+                    position: "absolute",
+                    top: 0,
+                    left: 0,
+
+                    height: "100%",
+                    width: "100%",
+
+                    pointerEvents: "none",
+                }}
+            >
+                <polyline
+                    points={kind.points.map(point => getMapPosition(map, point).join(",")).join(" ")}
+                    fill="none"
+                    stroke={kAnnotationColor}
+                    strokeWidth={3}
+                    strokeLinecap="round"
+                    strokeLinejoin="round"
+                    vectorEffect="non-scaling-stroke"
+                />
+            </svg>
+        );
+    }
+
+    const [posX, posY] = getMapPosition(map, kind.position);
+    const markerSize = kind.type === "ping" ? iconSize * 1.5 : iconSize * 0.75;
+    return (
+        <Box
+            sx={{
+                top: "var(--pos-y)",
+                left: "var(--pos-x)",
+
+                height: `${markerSize}%`,
+                width: `${markerSize}%`,
+
+                position: "absolute",
+                pointerEvents: "none",
+
+                borderRadius: "50%",
+                border: `2px solid ${kAnnotationColor}`,
+                backgroundColor: kind.type === "marker" ? kAnnotationColor : undefined,
+
+                "@keyframes annotation-ping": {
+                    from: { transform: "scale(0.5)", opacity: 1 },
+                    to: { transform: "scale(1.5)", opacity: 0.25 },
+                },
+                animation: kind.type === "ping" ? "annotation-ping 1s ease-out infinite" : undefined,
+            }}
+            style={
+                {
+                    "--pos-x": `${posX - markerSize / 2}%`,
+                    "--pos-y": `${posY - markerSize / 2}%`,
+                } as any
+            }
+        >
+            {kind.type === "marker" && kind.label && (
+                <Typography
+                    variant="caption"
+                    sx={{
+                        position: "absolute",
+                        top: "100%",
+                        left: "50%",
+                        transform: "translateX(-50%)",
+
+                        color: kAnnotationColor,
+                        whiteSpace: "nowrap",
+                        textShadow: "1px 1px 2px rgba(0, 0, 0, .75)",
+                    }}
+                >
+                    {kind.label}
+                </Typography>
+            )}
+        </Box>
+    );
+});
@@ -0,0 +1,281 @@
+use std::{
+    any::Any,
+    collections::{
+        BTreeSet,
+        HashMap,
+    },
+};
+
+use anyhow::Context;
+use serde::{
+    de::DeserializeOwned,
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+
+use crate::{
+    State,
+    StateRegistry,
+};
+
+/// Serialization hook of a state type.
+/// States opt in by returning it from [State::serializer].
+#[derive(Clone, Copy)]
+pub struct StateSerializer {
+    /// Name identifying the state type within a dump.
+    /// It must be unique and stable across builds so dumps remain loadable.
+    pub state_type: &'static str,
+
+    serialize: fn(
+        state_type: &'static str,
+        params: &dyn Any,
+        value: &dyn Any,
+    ) -> anyhow::Result<SerializedState>,
+    load: fn(states: &mut StateRegistry, state: &SerializedState) -> anyhow::Result<()>,
+}
+
+fn serialize_proxy<T>(
+    state_type: &'static str,
+    params: &dyn Any,
+    value: &dyn Any,
+) -> anyhow::Result<SerializedState>
+where
+    T: State + Serialize,
+    T::Parameter: Serialize,
+{
+    let params = params
+        .downcast_ref::<T::Parameter>()
+        .expect("to be of type T::Parameter");
+    let value = value.downcast_ref::<T>().expect("to be of type T");
+
+    Ok(SerializedState {
+        state_type: state_type.to_string(),
+        params: serde_json::to_value(params).context("params")?,
+        value: serde_json::to_value(value).context("value")?,
+    })
+}
+
+fn load_proxy<T>(states: &mut StateRegistry, state: &SerializedState) -> anyhow::Result<()>
+where
+    T: State + DeserializeOwned,
+    T::Parameter: DeserializeOwned,
+{
+    let params = T::Parameter::deserialize(&state.params).context("params")?;
+    let value = T::deserialize(&state.value).context("value")?;
+    states.set(value, params)
+}
+
+impl StateSerializer {
+    pub fn of<T>(state_type: &'static str) -> Self
+    where
+        T: State + Serialize + DeserializeOwned,
+        T::Parameter: Serialize + DeserializeOwned,
+    {
+        Self {
+            state_type,
+
+            serialize: serialize_proxy::<T>,
+            load: load_proxy::<T>,
+        }
+    }
+
+    pub(crate) fn serialize(
+        &self,
+        params: &dyn Any,
+        value: &dyn Any,
+    ) -> anyhow::Result<SerializedState> {
+        (self.serialize)(self.state_type, params, value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedState {
+    pub state_type: String,
+    pub params: Value,
+    pub value: Value,
+}
+
+/// Serialized live states of a [StateRegistry]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StateDump {
+    pub states: Vec<SerializedState>,
+}
+
+impl StateDump {
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(value: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(value)?)
+    }
+}
+
+/// Result of [StateDumpLoader::load]
+#[derive(Debug, Default)]
+pub struct StateDumpLoadResult {
+    /// Number of loaded states
+    pub loaded: usize,
+
+    /// State types contained in the dump which are not registered
+    pub skipped_types: BTreeSet<String>,
+}
+
+/// Rehydrates a [StateDump] into a registry.
+/// Only states of registered types will be loaded.
+#[derive(Default)]
+pub struct StateDumpLoader {
+    serializers: HashMap<&'static str, StateSerializer>,
+}
+
+impl StateDumpLoader {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Register a state type using the serializer returned by [State::serializer].
+    pub fn register<T: State>(&mut self) -> &mut Self {
+        let serializer = T::serializer().expect("state to provide a serializer");
+        let previous = self.serializers.insert(serializer.state_type, serializer);
+        assert!(
+            previous.is_none(),
+            "duplicate state type name {}",
+            serializer.state_type
+        );
+        self
+    }
+
+    /// Preset all states of registered types using [StateRegistry::set].
+    /// States of unknown types will be skipped and reported in the result.
+    pub fn load(
+        &self,
+        states: &mut StateRegistry,
+        dump: &StateDump,
+    ) -> anyhow::Result<StateDumpLoadResult> {
+        let mut result = StateDumpLoadResult::default();
+        for state in dump.states.iter() {
+            let Some(serializer) = self.serializers.get(state.state_type.as_str()) else {
+                result.skipped_types.insert(state.state_type.clone());
+                continue;
+            };
+
+            (serializer.load)(states, state)
+                .with_context(|| format!("load {}", state.state_type))?;
+            result.loaded += 1;
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{
+        Deserialize,
+        Serialize,
+    };
+    use serde_json::Value;
+
+    use super::{
+        SerializedState,
+        StateDump,
+        StateDumpLoader,
+        StateSerializer,
+    };
+    use crate::{
+        State,
+        StateCacheType,
+        StateRegistry,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct StatePlayer {
+        name: String,
+        health: i32,
+    }
+
+    impl State for StatePlayer {
+        type Parameter = u32;
+
+        fn create(_states: &StateRegistry, params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self {
+                name: format!("player {}", params),
+                health: 100,
+            })
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+
+        fn serializer() -> Option<StateSerializer> {
+            Some(StateSerializer::of::<Self>("player"))
+        }
+    }
+
+    struct StateOpaque;
+    impl State for StateOpaque {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+    }
+
+    #[test]
+    fn test_dump_roundtrip() {
+        let states = StateRegistry::new(8);
+        assert!(states.resolve::<StatePlayer>(1).is_ok());
+        assert!(states.resolve::<StatePlayer>(2).is_ok());
+        assert!(states.resolve::<StateOpaque>(()).is_ok());
+
+        let dump = states.dump_states().unwrap();
+        assert_eq!(dump.states.len(), 2);
+        assert!(dump.states.iter().all(|state| state.state_type == "player"));
+
+        let dump = StateDump::from_json(&dump.to_json().unwrap()).unwrap();
+        let mut loaded_states = StateRegistry::new(8);
+        let mut loader = StateDumpLoader::new();
+        loader.register::<StatePlayer>();
+        let result = loader.load(&mut loaded_states, &dump).unwrap();
+        assert_eq!(result.loaded, 2);
+        assert!(result.skipped_types.is_empty());
+
+        assert_eq!(
+            *loaded_states.get::<StatePlayer>(2).unwrap(),
+            StatePlayer {
+                name: "player 2".to_string(),
+                health: 100
+            }
+        );
+        assert!(loaded_states.get::<StateOpaque>(()).is_none());
+    }
+
+    #[test]
+    fn test_dump_unknown_types() {
+        let states = StateRegistry::new(8);
+        assert!(states.resolve::<StatePlayer>(1).is_ok());
+
+        let mut dump = states.dump_states().unwrap();
+        dump.states.push(SerializedState {
+            state_type: "removed-state".to_string(),
+            params: Value::Null,
+            value: Value::Null,
+        });
+
+        let mut loaded_states = StateRegistry::new(8);
+        let result = StateDumpLoader::new()
+            .load(&mut loaded_states, &dump)
+            .unwrap();
+        assert_eq!(result.loaded, 0);
+        assert_eq!(
+            result.skipped_types.into_iter().collect::<Vec<_>>(),
+            vec!["player", "removed-state"]
+        );
+    }
+}
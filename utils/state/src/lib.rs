@@ -6,12 +6,14 @@ use std::{
         TypeId,
     },
     cell::{
+        Cell,
+        OnceCell,
         Ref,
         RefCell,
         RefMut,
     },
     collections::{
-        hash_map::Entry,
+        BTreeMap,
         HashMap,
     },
     hash::{
@@ -19,7 +21,10 @@ use std::{
         Hash,
         Hasher,
     },
-    ops::DerefMut,
+    ops::{
+        DerefMut,
+        Range,
+    },
     time::{
         Duration,
         Instant,
@@ -31,6 +36,15 @@ use anyhow::{
     Context,
 };
 
+mod dump;
+pub use dump::*;
+
+mod sync;
+pub use sync::*;
+
+mod trace;
+pub use trace::*;
+
 pub enum StateCacheType {
     /// The state will be cached and never removed
     Persistent,
@@ -42,10 +56,17 @@ pub enum StateCacheType {
     /// The state will be removed as soon it get's invalidated.
     /// The update method will only be called once uppon creation.
     Volatile,
+
+    /// The state will be cached and never removed, but only be updated
+    /// once the refresh interval elapsed (see [State::refresh]). If the refresh fails,
+    /// the last good value will be served and reported as stale (see [StateRegistry::freshness]).
+    Refresh(Duration),
 }
 
 pub trait State: Any + Sized + Send {
-    type Parameter: Hash + PartialEq;
+    /// Parameter identifying the state instance.
+    /// Instances are looked up by the parameter hash and compared for equality.
+    type Parameter: Hash + PartialEq + Clone + Send + 'static;
 
     /// Create a new instance of this state.
     /// Note: update will be called after creation automatically.
@@ -62,6 +83,26 @@ pub trait State: Any + Sized + Send {
     fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Refresh a [StateCacheType::Refresh] state without altering the current value.
+    /// The returned value replaces the current value on success.
+    ///
+    /// Returns `None` by default which refreshes the current value in place using [State::update].
+    fn refresh(&self, _states: &StateRegistry) -> Option<anyhow::Result<Self>> {
+        None
+    }
+
+    /// Tags of the state which can be used to invalidate
+    /// a group of states via [StateRegistry::invalidate_tag].
+    fn tags() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Opt in to be included in [StateRegistry::dump_states]
+    /// by returning `Some(StateSerializer::of::<Self>("<stable name>"))`.
+    fn serializer() -> Option<StateSerializer> {
+        None
+    }
 }
 
 fn value_update_proxy<T: State>(
@@ -72,66 +113,159 @@ fn value_update_proxy<T: State>(
     value.update(states)
 }
 
+/// Slot index and id of a state instance
+type StateInstance = (usize, u64);
+
 struct InternalState {
+    /// Unique id of this state instance as slots will be reused
+    id: u64,
+
     value: Box<dyn Any + Send>,
     value_update: fn(&mut Box<dyn Any + Send>, states: &StateRegistry) -> anyhow::Result<()>,
+    value_type: &'static str,
+    tags: &'static [&'static str],
+    serializer: Option<StateSerializer>,
 
     cache_key: (TypeId, u64),
     cache_type: StateCacheType,
 
+    /// States which have resolved this state while being created or updated
+    dependents: RefCell<Vec<StateInstance>>,
+
     dirty: bool,
     last_access: Instant,
+
+    /// Time of the last update attempt
+    last_refresh: Instant,
+    /// Time of the last successful update
+    last_update: Option<Instant>,
+    /// Error of the last update attempt
+    last_error: Option<String>,
+}
+
+/// Update status of a state instance
+#[derive(Debug, Clone)]
+pub struct StateFreshness {
+    /// Time of the last successful update
+    pub last_update: Option<Instant>,
+
+    /// Error of the last update if it failed.
+    /// For `Refresh` states the last good value will be served instead.
+    pub last_error: Option<String>,
+}
+
+impl StateFreshness {
+    /// Time since the last successful update
+    pub fn age(&self) -> Option<Duration> {
+        self.last_update.map(|last_update| last_update.elapsed())
+    }
+
+    /// The value has not been updated as the last update failed
+    pub fn is_stale(&self) -> bool {
+        self.last_error.is_some()
+    }
+}
+
+/// Cache key of a state instance.
+/// Multiple instances may share the same key as the parameter hash may collide.
+pub(crate) fn state_key<T: State>(params: &T::Parameter) -> (TypeId, u64) {
+    let mut hasher = DefaultHasher::new();
+    params.hash(&mut hasher);
+    (TypeId::of::<T>(), hasher.finish())
 }
 
+pub(crate) fn params_equal<T: State>(stored_params: &dyn Any, params: &T::Parameter) -> bool {
+    stored_params
+        .downcast_ref::<T::Parameter>()
+        .is_some_and(|stored_params| stored_params == params)
+}
+
+/// Parameters of a state instance and the index of the state
+type StateEntry = (Box<dyn Any + Send>, usize);
+
 struct StateAllocator {
-    index_lookup: HashMap<(TypeId, u64), usize>,
+    /// State entries by the cache key
+    index_lookup: HashMap<(TypeId, u64), Vec<StateEntry>>,
     free_list: Vec<usize>,
+
+    /// Total number of allocated slots
+    capacity: usize,
 }
 
 impl StateAllocator {
-    pub fn new(capacity: usize) -> Self {
-        let mut free_list = Vec::with_capacity(capacity);
-        for index in (0..capacity).rev() {
-            free_list.push(index);
-        }
-
+    pub fn new() -> Self {
         Self {
             index_lookup: Default::default(),
-            free_list,
+            free_list: Default::default(),
+            capacity: 0,
         }
     }
 
+    fn add_slots(&mut self, slots: Range<usize>) {
+        self.capacity += slots.len();
+        self.free_list.extend(slots.rev());
+    }
+
+    fn live_states(&self) -> usize {
+        self.capacity - self.free_list.len()
+    }
+
+    fn entry_params(&self, cache_key: &(TypeId, u64), index: usize) -> Option<&dyn Any> {
+        self.index_lookup
+            .get(cache_key)?
+            .iter()
+            .find(|(_, entry_index)| *entry_index == index)
+            .map(|(params, _)| params.as_ref() as &dyn Any)
+    }
+
     fn calculate_state_index<T: State>(
         &mut self,
         params: &T::Parameter,
         create_if_not_exists: bool,
     ) -> Option<((TypeId, u64), usize)> {
-        let mut hasher = DefaultHasher::new();
-        params.hash(&mut hasher);
-        let params_hash = hasher.finish();
-
-        let cache_key = (TypeId::of::<T>(), params_hash);
-        let index = match self.index_lookup.entry(cache_key) {
-            Entry::Occupied(entry) => *entry.get(),
-            Entry::Vacant(entry) => {
-                if !create_if_not_exists {
-                    /* Do not create the target entry */
-                    return None;
-                }
+        let cache_key = state_key::<T>(params);
+        let existing_index = self.index_lookup.get(&cache_key).and_then(|entries| {
+            entries
+                .iter()
+                .find(|(entry_params, _)| params_equal::<T>(entry_params.as_ref(), params))
+                .map(|(_, index)| *index)
+        });
 
-                let index = self.free_list.pop()?;
-                *entry.insert(index)
-            }
-        };
+        if let Some(index) = existing_index {
+            return Some((cache_key, index));
+        }
+
+        if !create_if_not_exists {
+            /* Do not create the target entry */
+            return None;
+        }
+
+        let index = self.free_list.pop()?;
+        self.index_lookup
+            .entry(cache_key)
+            .or_default()
+            .push((Box::new(params.clone()), index));
 
         Some((cache_key, index))
     }
 
-    fn free_entry(&mut self, cache_key: &(TypeId, u64)) {
-        let index = match self.index_lookup.remove(cache_key) {
-            Some(index) => index,
-            None => return,
+    fn free_entry(&mut self, cache_key: &(TypeId, u64), index: usize) {
+        let Some(entries) = self.index_lookup.get_mut(cache_key) else {
+            return;
+        };
+
+        let Some(position) = entries
+            .iter()
+            .position(|(_, entry_index)| *entry_index == index)
+        else {
+            return;
         };
+
+        entries.swap_remove(position);
+        if entries.is_empty() {
+            self.index_lookup.remove(cache_key);
+        }
+
         self.free_list.push(index);
     }
 }
@@ -152,27 +286,495 @@ fn transpose_ref_mut_opt<T>(x: RefMut<'_, Option<T>>) -> Option<RefMut<'_, T>> {
     }
 }
 
+/// Leaves the traced state once dropped
+struct TraceScope<'a> {
+    trace: Option<&'a RefCell<Option<TraceRecorder>>>,
+}
+
+impl Drop for TraceScope<'_> {
+    fn drop(&mut self) {
+        let Some(trace) = self.trace else {
+            return;
+        };
+
+        if let Some(recorder) = trace.borrow_mut().as_mut() {
+            recorder.leave();
+        }
+    }
+}
+
+/// Capacity limits of the [StateRegistry]
+#[derive(Debug, Clone, Copy)]
+pub struct StateLimits {
+    /// Number of state slots allocated upfront.
+    /// Every time the registry runs out of slots, it doubles its capacity.
+    pub initial_capacity: usize,
+
+    /// Number of live states above which the least recently used
+    /// `Timed` states will be evicted on invalidation, even if not yet expired.
+    pub soft_limit: usize,
+
+    /// Maximum number of states. Once reached, the least recently used `Timed` state
+    /// which has not been accessed within the current frame will be evicted to make room.
+    pub hard_limit: usize,
+}
+
+impl Default for StateLimits {
+    fn default() -> Self {
+        Self {
+            initial_capacity: 1024,
+            soft_limit: 1024 * 8,
+            hard_limit: 1024 * 64,
+        }
+    }
+}
+
+/// Occupancy of a single state type
+#[derive(Debug, Clone, Default)]
+pub struct StateTypeStatistics {
+    /// Number of live instances
+    pub instances: usize,
+
+    /// Number of instances evicted before they expired
+    pub evictions: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StateRegistryStatistics {
+    /// Number of allocated state slots
+    pub capacity: usize,
+    pub live_states: usize,
+    pub limits: Option<StateLimits>,
+
+    /// Statistics by the state type
+    pub types: BTreeMap<&'static str, StateTypeStatistics>,
+}
+
+/// State slots are allocated in chunks.
+/// Allocated chunks never move, hence borrowed states stay valid while the registry grows.
+type StateChunk = Box<[RefCell<Option<InternalState>>]>;
+
 pub struct StateRegistry {
     allocator: RefCell<StateAllocator>,
-    states: Vec<RefCell<Option<InternalState>>>,
+    limits: StateLimits,
+
+    /// First slot index of each chunk followed by the total number of slots
+    chunk_offsets: Vec<usize>,
+    chunks: Box<[OnceCell<StateChunk>]>,
+    evictions: RefCell<BTreeMap<&'static str, usize>>,
+
+    next_state_id: Cell<u64>,
+    /// States currently being created or updated
+    resolve_stack: RefCell<Vec<StateInstance>>,
+
+    trace: RefCell<Option<TraceRecorder>>,
+    last_trace: Option<ResolveTrace>,
 }
 
 impl StateRegistry {
+    /// Create a new registry with an initial capacity which grows without limits
     pub fn new(capacity: usize) -> Self {
-        let mut states = Vec::with_capacity(capacity);
-        states.resize_with(capacity, Default::default);
-        Self {
-            allocator: RefCell::new(StateAllocator::new(capacity)),
-            states,
+        Self::with_limits(StateLimits {
+            initial_capacity: capacity,
+            soft_limit: usize::MAX,
+            hard_limit: usize::MAX,
+        })
+    }
+
+    pub fn with_limits(limits: StateLimits) -> Self {
+        let mut chunk_offsets = Vec::new();
+        let mut chunk_offset = 0usize;
+        let mut chunk_size = limits.initial_capacity.max(1);
+        while chunk_offset < limits.hard_limit {
+            chunk_offsets.push(chunk_offset);
+            chunk_offset = chunk_offset.saturating_add(chunk_size);
+            chunk_size = chunk_size.saturating_mul(2);
+        }
+        chunk_offsets.push(chunk_offset.min(limits.hard_limit));
+
+        let mut chunks = Vec::with_capacity(chunk_offsets.len() - 1);
+        chunks.resize_with(chunk_offsets.len() - 1, OnceCell::new);
+
+        let registry = Self {
+            allocator: RefCell::new(StateAllocator::new()),
+            limits,
+
+            chunk_offsets,
+            chunks: chunks.into_boxed_slice(),
+            evictions: Default::default(),
+
+            next_state_id: Cell::new(1),
+            resolve_stack: Default::default(),
+
+            trace: RefCell::new(None),
+            last_trace: None,
+        };
+        registry.grow();
+        registry
+    }
+
+    pub fn limits(&self) -> &StateLimits {
+        &self.limits
+    }
+
+    /// Allocate the next chunk of state slots.
+    /// Returns false if the hard limit has been reached.
+    fn grow(&self) -> bool {
+        let Some(chunk_index) = self.chunks.iter().position(|chunk| chunk.get().is_none()) else {
+            return false;
+        };
+
+        let slots = self.chunk_offsets[chunk_index]..self.chunk_offsets[chunk_index + 1];
+        let mut chunk = Vec::with_capacity(slots.len());
+        chunk.resize_with(slots.len(), Default::default);
+        let _ = self.chunks[chunk_index].set(chunk.into_boxed_slice());
+
+        self.allocator.borrow_mut().add_slots(slots);
+        true
+    }
+
+    fn slot(&self, index: usize) -> &RefCell<Option<InternalState>> {
+        let chunk_index = self
+            .chunk_offsets
+            .partition_point(|offset| *offset <= index)
+            - 1;
+
+        let chunk = self.chunks[chunk_index]
+            .get()
+            .expect("slot to be allocated");
+        &chunk[index - self.chunk_offsets[chunk_index]]
+    }
+
+    /// All allocated slots with their index
+    fn slots(&self) -> impl Iterator<Item = (usize, &RefCell<Option<InternalState>>)> {
+        self.chunks
+            .iter()
+            .zip(self.chunk_offsets.iter())
+            .filter_map(|(chunk, offset)| Some((chunk.get()?, *offset)))
+            .flat_map(|(chunk, offset)| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, slot)| (offset + index, slot))
+            })
+    }
+
+    fn evict_state(
+        &self,
+        allocator: &mut StateAllocator,
+        index: usize,
+        state: &mut Option<InternalState>,
+    ) {
+        let Some(state) = state.take() else {
+            return;
+        };
+
+        allocator.free_entry(&state.cache_key, index);
+        *self
+            .evictions
+            .borrow_mut()
+            .entry(state.value_type)
+            .or_default() += 1;
+    }
+
+    /// Evict the least recently used `Timed` state which has not been accessed within this frame.
+    /// Returns false if no such state exists.
+    fn evict_least_recently_used(&self) -> bool {
+        let mut candidate: Option<(Instant, usize)> = None;
+        for (index, slot) in self.slots() {
+            let Ok(state) = slot.try_borrow_mut() else {
+                /* state is currently in use */
+                continue;
+            };
+
+            let Some(state) = state.as_ref() else {
+                continue;
+            };
+
+            if !matches!(state.cache_type, StateCacheType::Timed(_)) || !state.dirty {
+                continue;
+            }
+
+            if candidate.is_none_or(|(last_access, _)| state.last_access < last_access) {
+                candidate = Some((state.last_access, index));
+            }
+        }
+
+        let Some((_, index)) = candidate else {
+            return false;
+        };
+
+        let mut state = self.slot(index).borrow_mut();
+        self.evict_state(&mut self.allocator.borrow_mut(), index, &mut state);
+        true
+    }
+
+    fn allocate_state_index<T: State>(
+        &self,
+        params: &T::Parameter,
+    ) -> anyhow::Result<((TypeId, u64), usize)> {
+        loop {
+            if let Some(result) = self
+                .allocator
+                .borrow_mut()
+                .calculate_state_index::<T>(params, true)
+            {
+                return Ok(result);
+            }
+
+            if !self.grow() && !self.evict_least_recently_used() {
+                anyhow::bail!("state capacity exceeded");
+            }
+        }
+    }
+
+    /// Occupancy statistics of the registry
+    pub fn statistics(&self) -> StateRegistryStatistics {
+        let allocator = self.allocator.borrow();
+        let mut result = StateRegistryStatistics {
+            capacity: allocator.capacity,
+            live_states: allocator.live_states(),
+            limits: Some(self.limits),
+            types: Default::default(),
+        };
+
+        for (state_type, instances) in self.instance_counts() {
+            result.types.entry(state_type).or_default().instances = instances;
+        }
+
+        for (state_type, evictions) in self.evictions.borrow().iter() {
+            result.types.entry(state_type).or_default().evictions = *evictions;
+        }
+
+        result
+    }
+
+    /// Enable or disable recording which states have been resolved within a frame.
+    /// The trace of the last frame is available via [StateRegistry::last_trace].
+    pub fn set_tracing(&mut self, enabled: bool) {
+        let trace = self.trace.get_mut();
+        if enabled {
+            trace.get_or_insert_with(TraceRecorder::new);
+        } else {
+            *trace = None;
+            self.last_trace = None;
+        }
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.trace.borrow().is_some()
+    }
+
+    /// The resolve trace of the last frame (if tracing is enabled)
+    pub fn last_trace(&self) -> Option<&ResolveTrace> {
+        self.last_trace.as_ref()
+    }
+
+    /// Number of live state instances by their state type
+    pub fn instance_counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut result = BTreeMap::new();
+        for (_index, state) in self.slots() {
+            let Ok(state) = state.try_borrow() else {
+                /* state is currently being resolved */
+                continue;
+            };
+
+            if let Some(state) = state.as_ref() {
+                *result.entry(state.value_type).or_default() += 1;
+            }
+        }
+
+        result
+    }
+
+    fn trace_enter<T: State>(&self, index: usize) -> TraceScope<'_> {
+        match self.trace.borrow_mut().as_mut() {
+            Some(recorder) => {
+                recorder.enter(index, any::type_name::<T>());
+                TraceScope {
+                    trace: Some(&self.trace),
+                }
+            }
+            None => TraceScope { trace: None },
+        }
+    }
+
+    fn trace_record(&self, index: usize, callback: impl FnOnce(&mut StateTraceNode)) {
+        if let Some(node) = self
+            .trace
+            .borrow_mut()
+            .as_mut()
+            .and_then(|recorder| recorder.node_mut(index))
+        {
+            callback(node);
+        }
+    }
+
+    fn allocate_state_id(&self) -> u64 {
+        let id = self.next_state_id.get();
+        self.next_state_id.set(id + 1);
+        id
+    }
+
+    /// Run the callback while the state is marked as being resolved
+    /// in order to record the states it depends on.
+    fn with_dependent<R>(&self, instance: StateInstance, callback: impl FnOnce() -> R) -> R {
+        self.resolve_stack.borrow_mut().push(instance);
+        let result = callback();
+        self.resolve_stack.borrow_mut().pop();
+        result
+    }
+
+    fn record_dependent(&self, state: &InternalState) {
+        let Some(dependent) = self.resolve_stack.borrow().last().copied() else {
+            return;
+        };
+
+        let mut dependents = state.dependents.borrow_mut();
+        if !dependents.contains(&dependent) {
+            dependents.push(dependent);
+        }
+    }
+
+    fn is_instance_alive(&self, (index, id): StateInstance) -> bool {
+        self.slot(index)
+            .try_borrow()
+            .is_ok_and(|state| state.as_ref().is_some_and(|state| state.id == id))
+    }
+
+    /// Remove the target states and all states depending on them.
+    /// Returns the number of removed states.
+    fn remove_states(&mut self, mut pending: Vec<usize>) -> usize {
+        let mut removed = 0;
+        while let Some(index) = pending.pop() {
+            let Some(state) = self.slot(index).borrow_mut().take() else {
+                /* already removed */
+                continue;
+            };
+
+            self.allocator.get_mut().free_entry(&state.cache_key, index);
+            removed += 1;
+
+            for dependent in state.dependents.into_inner() {
+                if self.is_instance_alive(dependent) {
+                    pending.push(dependent.0);
+                }
+            }
+        }
+
+        removed
+    }
+
+    /// Remove all states with the given tag including their dependents.
+    /// Returns the number of removed states.
+    pub fn invalidate_tag(&mut self, tag: &str) -> usize {
+        let targets = self
+            .slots()
+            .filter(|(_, state)| {
+                state
+                    .borrow()
+                    .as_ref()
+                    .is_some_and(|state| state.tags.contains(&tag))
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        self.remove_states(targets)
+    }
+
+    /// Remove all states of type T matching the predicate including their dependents.
+    /// Returns the number of removed states.
+    pub fn invalidate_where<T: State>(
+        &mut self,
+        predicate: impl Fn(&T::Parameter, &T) -> bool,
+    ) -> usize {
+        let allocator = self.allocator.borrow();
+        let targets = self
+            .slots()
+            .filter(|(index, state)| {
+                let state = state.borrow();
+                let Some(state) = state.as_ref() else {
+                    return false;
+                };
+
+                if state.cache_key.0 != TypeId::of::<T>() {
+                    return false;
+                }
+
+                let Some(params) = allocator
+                    .entry_params(&state.cache_key, *index)
+                    .and_then(|params| params.downcast_ref::<T::Parameter>())
+                else {
+                    return false;
+                };
+
+                let value = state.value.downcast_ref::<T>().expect("to be of type T");
+                predicate(params, value)
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        drop(allocator);
+
+        self.remove_states(targets)
+    }
+
+    /// Remove a specific state including its dependents.
+    /// Returns the number of removed states.
+    pub fn invalidate<T: State>(&mut self, params: T::Parameter) -> usize {
+        let Some((_cache_key, index)) = self
+            .allocator
+            .get_mut()
+            .calculate_state_index::<T>(&params, false)
+        else {
+            return 0;
+        };
+
+        self.remove_states(vec![index])
+    }
+
+    /// Serialize all live states which provide a [StateSerializer].
+    /// States which are currently borrowed mutably will be skipped.
+    pub fn dump_states(&self) -> anyhow::Result<StateDump> {
+        let allocator = self.allocator.borrow();
+        let mut result = StateDump::default();
+        for (index, state) in self.slots() {
+            let Ok(state) = state.try_borrow() else {
+                continue;
+            };
+
+            let Some(state) = state.as_ref() else {
+                continue;
+            };
+
+            let Some(serializer) = &state.serializer else {
+                continue;
+            };
+
+            let params = allocator
+                .entry_params(&state.cache_key, index)
+                .context("missing state params")?;
+
+            let state = serializer
+                .serialize(params, state.value.as_ref())
+                .with_context(|| format!("serialize {}", state.value_type))?;
+            result.states.push(state);
         }
+
+        Ok(result)
     }
 
     pub fn invalidate_states(&mut self) {
+        if let Some(recorder) = self.trace.get_mut().take() {
+            self.last_trace = Some(recorder.finish());
+            *self.trace.get_mut() = Some(TraceRecorder::new());
+        }
+
         /* As we're mutable there should be no more references to the underlying state */
         let mut allocator = self.allocator.borrow_mut();
 
         let now = Instant::now();
-        for state in self.states.iter_mut() {
+        let mut eviction_candidates = Vec::new();
+        for (index, state) in self.slots() {
             let mut state_ref = state.borrow_mut();
             let state = if let Some(state) = state_ref.deref_mut() {
                 state
@@ -186,47 +788,102 @@ impl StateRegistry {
                 state.dirty = true;
             }
 
+            if let StateCacheType::Refresh(interval) = state.cache_type {
+                /* only update the state once the refresh interval elapsed */
+                state.dirty = state.last_update.is_none()
+                    || now.duration_since(state.last_refresh) >= interval;
+            }
+
             let state_expired = match state.cache_type {
                 StateCacheType::Persistent => false,
+                StateCacheType::Refresh(_) => false,
                 StateCacheType::Volatile => true,
                 StateCacheType::Timed(timeout) => state.last_access.elapsed() > timeout,
             };
             if state_expired {
-                allocator.free_entry(&state.cache_key);
+                allocator.free_entry(&state.cache_key, index);
                 *state_ref = None;
+            } else if matches!(state.cache_type, StateCacheType::Timed(_)) {
+                eviction_candidates.push((state.last_access, index));
+            }
+        }
+
+        drop(allocator);
+        for (_index, state) in self.slots() {
+            if let Some(state) = state.borrow().as_ref() {
+                state
+                    .dependents
+                    .borrow_mut()
+                    .retain(|dependent| self.is_instance_alive(*dependent));
+            }
+        }
+
+        let mut allocator = self.allocator.borrow_mut();
+        let excess_states = allocator
+            .live_states()
+            .saturating_sub(self.limits.soft_limit);
+        if excess_states > 0 {
+            eviction_candidates.sort_by_key(|(last_access, _)| *last_access);
+            for (_, index) in eviction_candidates.into_iter().take(excess_states) {
+                let mut state = self.slot(index).borrow_mut();
+                self.evict_state(&mut allocator, index, &mut state);
             }
         }
     }
 
     /// Preset a specific state
     pub fn set<T: State>(&mut self, value: T, params: T::Parameter) -> anyhow::Result<()> {
-        let (cache_key, index) = self
-            .allocator
-            .borrow_mut()
-            .calculate_state_index::<T>(&params, true)
-            .context("state capacity exceeded")?;
+        let (cache_key, index) = self.allocate_state_index::<T>(&params)?;
 
-        let mut state_ref = self.states[index].borrow_mut();
+        let mut state_ref = self.slot(index).borrow_mut();
         *state_ref = Some(InternalState {
+            id: self.allocate_state_id(),
+
             value: Box::new(value),
             value_update: value_update_proxy::<T>,
+            value_type: any::type_name::<T>(),
+            tags: T::tags(),
+            serializer: T::serializer(),
 
             cache_key,
             cache_type: T::cache_type(),
 
+            dependents: Default::default(),
+
             dirty: false,
             last_access: Instant::now(),
+
+            last_refresh: Instant::now(),
+            last_update: Some(Instant::now()),
+            last_error: None,
         });
         Ok(())
     }
 
+    /// Update status of a state instance.
+    /// Returns None if the state does not exist or is currently borrowed mutably.
+    pub fn freshness<T: State>(&self, params: T::Parameter) -> Option<StateFreshness> {
+        let (_cache_key, index) = self
+            .allocator
+            .borrow_mut()
+            .calculate_state_index::<T>(&params, false)?;
+
+        let state = self.slot(index).try_borrow().ok()?;
+        let state = state.as_ref()?;
+        Some(StateFreshness {
+            last_update: state.last_update,
+            last_error: state.last_error.clone(),
+        })
+    }
+
     pub fn get<T: State>(&self, params: T::Parameter) -> Option<Ref<'_, T>> {
         let (_cache_key, index) = self
             .allocator
             .borrow_mut()
             .calculate_state_index::<T>(&params, false)?;
 
-        let value = self.states[index]
+        let value = self
+            .slot(index)
             .try_borrow()
             .ok()
             .map(transpose_ref_opt)
@@ -245,7 +902,8 @@ impl StateRegistry {
             .borrow_mut()
             .calculate_state_index::<T>(&params, false)?;
 
-        let value = self.states[index]
+        let value = self
+            .slot(index)
             .try_borrow_mut()
             .ok()
             .map(transpose_ref_mut_opt)
@@ -261,26 +919,54 @@ impl StateRegistry {
     fn initialize_value<T: State>(
         &self,
         cache_key: (TypeId, u64),
+        index: usize,
         value: &mut RefMut<'_, Option<InternalState>>,
         params: T::Parameter,
     ) -> anyhow::Result<()> {
+        let mut cache_hit = true;
         let value = match value.as_mut() {
             Some(value) => value,
             None => {
                 /* create a new value */
-                let state = Box::new(
-                    T::create(self, params)
-                        .with_context(|| format!("create {}", any::type_name::<T>()))?,
-                );
+                cache_hit = false;
+
+                let id = self.allocate_state_id();
+                let create_begin = Instant::now();
+                let state = self.with_dependent((index, id), || T::create(self, params));
+                self.trace_record(index, |node| {
+                    node.cache_misses += 1;
+                    node.create_time += create_begin.elapsed();
+                    node.errors += state.is_err() as usize;
+                });
+
+                let state = match state {
+                    Ok(state) => Box::new(state),
+                    Err(err) => {
+                        /* release the allocated entry as there is no state to evict later on */
+                        self.allocator.borrow_mut().free_entry(&cache_key, index);
+                        return Err(err.context(format!("create {}", any::type_name::<T>())));
+                    }
+                };
                 **value = Some(InternalState {
+                    id,
+
                     value: state,
                     value_update: value_update_proxy::<T>,
+                    value_type: any::type_name::<T>(),
+                    tags: T::tags(),
+                    serializer: T::serializer(),
 
                     cache_key,
                     cache_type: T::cache_type(),
 
+                    dependents: Default::default(),
+
                     dirty: true,
                     last_access: Instant::now(),
+
+                    last_refresh: Instant::now(),
+                    last_update: None,
+                    last_error: None,
                 });
 
                 value.as_mut().unwrap()
@@ -288,27 +974,72 @@ impl StateRegistry {
         };
 
         if value.dirty {
-            (value.value_update)(&mut value.value, self)
-                .with_context(|| format!("update {}", any::type_name::<T>()))?;
+            cache_hit = false;
+
+            let update_begin = Instant::now();
+            let result = if matches!(value.cache_type, StateCacheType::Refresh(_))
+                && value.last_update.is_some()
+            {
+                self.with_dependent((index, value.id), || {
+                    let current = value.value.downcast_ref::<T>().expect("to be of type T");
+                    match current.refresh(self) {
+                        Some(refreshed) => refreshed.map(|refreshed| {
+                            value.value = Box::new(refreshed);
+                        }),
+                        None => (value.value_update)(&mut value.value, self),
+                    }
+                })
+            } else {
+                self.with_dependent((index, value.id), || {
+                    (value.value_update)(&mut value.value, self)
+                })
+            };
+            self.trace_record(index, |node| {
+                node.updates += 1;
+                node.update_time += update_begin.elapsed();
+                node.errors += result.is_err() as usize;
+            });
+
+            value.last_refresh = Instant::now();
+            match result {
+                Ok(()) => {
+                    value.last_update = Some(value.last_refresh);
+                    value.last_error = None;
+                }
+                Err(err)
+                    if matches!(value.cache_type, StateCacheType::Refresh(_))
+                        && value.last_update.is_some() =>
+                {
+                    /* keep serving the last good value until the next refresh */
+                    value.last_error = Some(format!("{:#}", err));
+                }
+                Err(err) => {
+                    value.last_error = Some(format!("{:#}", err));
+                    return Err(err).with_context(|| format!("update {}", any::type_name::<T>()));
+                }
+            }
             value.dirty = false;
         }
 
+        if cache_hit {
+            self.trace_record(index, |node| node.cache_hits += 1);
+        }
+
         Ok(())
     }
 
     pub fn resolve_mut<T: State>(&self, params: T::Parameter) -> anyhow::Result<RefMut<'_, T>> {
-        let (cache_key, index) = self
-            .allocator
-            .borrow_mut()
-            .calculate_state_index::<T>(&params, true)
-            .context("state capacity exceeded")?;
+        let (cache_key, index) = self.allocate_state_index::<T>(&params)?;
 
-        let mut value = self.states[index]
+        let _trace_scope = self.trace_enter::<T>(index);
+        let mut value = self
+            .slot(index)
             .try_borrow_mut()
             .context("value already borrowed")?;
 
-        self.initialize_value::<T>(cache_key, &mut value, params)?;
+        self.initialize_value::<T>(cache_key, index, &mut value, params)?;
         let value = transpose_ref_mut_opt(value).context("expected a valid value")?;
+        self.record_dependent(&value);
 
         Ok(RefMut::map(value, |value| {
             value.value.downcast_mut::<T>().expect("to be of type T")
@@ -316,27 +1047,27 @@ impl StateRegistry {
     }
 
     pub fn resolve<T: State>(&self, params: T::Parameter) -> anyhow::Result<Ref<'_, T>> {
-        let (cache_key, index) = self
-            .allocator
-            .borrow_mut()
-            .calculate_state_index::<T>(&params, true)
-            .context("state capacity exceeded")?;
+        let (cache_key, index) = self.allocate_state_index::<T>(&params)?;
 
-        if let Ok(mut value) = self.states[index].try_borrow_mut() {
-            self.initialize_value::<T>(cache_key, &mut value, params)?;
+        let _trace_scope = self.trace_enter::<T>(index);
+        if let Ok(mut value) = self.slot(index).try_borrow_mut() {
+            self.initialize_value::<T>(cache_key, index, &mut value, params)?;
         } else {
             /* We already borrowed that state, hence it must be initialized & not dirty */
+            self.trace_record(index, |node| node.cache_hits += 1);
         }
 
-        let value = self.states[index].try_borrow().map_err(|_| {
+        let value = self.slot(index).try_borrow().map_err(|_| {
             anyhow!(
                 "circular state initialisation for {}",
                 any::type_name::<T>()
             )
         })?;
 
+        let value = Ref::map(value, |value| value.as_ref().expect("to be present"));
+        self.record_dependent(&value);
+
         let value = Ref::map(value, |value| {
-            let value = value.as_ref().expect("to be present");
             value.value.downcast_ref::<T>().expect("to be of type T")
         });
         Ok(value)
@@ -345,9 +1076,23 @@ impl StateRegistry {
 
 #[cfg(test)]
 mod test {
+    use std::{
+        hash::{
+            Hash,
+            Hasher,
+        },
+        sync::atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        thread,
+        time::Duration,
+    };
+
     use super::{
         State,
         StateCacheType,
+        StateLimits,
         StateRegistry,
     };
 
@@ -412,6 +1157,394 @@ mod test {
         assert!(states.resolve::<StateC>(0).is_ok());
     }
 
+    /// State which can not be created
+    struct StateFailing;
+    impl State for StateFailing {
+        type Parameter = u64;
+    }
+
+    #[test]
+    fn test_creation_failed() {
+        let states = StateRegistry::new(4);
+        for index in 0..8 {
+            assert!(states.resolve::<StateFailing>(index).is_err());
+        }
+
+        /* failed creations must not keep their entries allocated */
+        assert_eq!(states.statistics().live_states, 0);
+        assert!(states.resolve::<StateA>(()).is_ok());
+        assert_eq!(states.statistics().live_states, 1);
+    }
+
+    /// Parameter where all values share the same hash
+    #[derive(Clone, PartialEq)]
+    struct CollidingParam(u64);
+    impl Hash for CollidingParam {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            0u64.hash(state);
+        }
+    }
+
+    struct StateD(u64);
+    impl State for StateD {
+        type Parameter = CollidingParam;
+
+        fn create(_states: &StateRegistry, params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(params.0))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Volatile
+        }
+    }
+
+    #[test]
+    fn test_hash_collision() {
+        let mut states = StateRegistry::new(4);
+        assert_eq!(states.resolve::<StateD>(CollidingParam(1)).unwrap().0, 1);
+        assert_eq!(states.resolve::<StateD>(CollidingParam(2)).unwrap().0, 2);
+        assert_eq!(states.get::<StateD>(CollidingParam(1)).unwrap().0, 1);
+        assert!(states.get::<StateD>(CollidingParam(3)).is_none());
+
+        /* freed slots must be reusable */
+        states.invalidate_states();
+        assert!(states.get::<StateD>(CollidingParam(1)).is_none());
+        for value in 0..4 {
+            assert_eq!(
+                states.resolve::<StateD>(CollidingParam(value)).unwrap().0,
+                value
+            );
+        }
+    }
+
+    struct StateE;
+    impl State for StateE {
+        type Parameter = u64;
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Timed(Duration::from_secs(60))
+        }
+    }
+
+    #[test]
+    fn test_grow() {
+        let states = StateRegistry::with_limits(StateLimits {
+            initial_capacity: 2,
+            soft_limit: usize::MAX,
+            hard_limit: 7,
+        });
+        assert_eq!(states.statistics().capacity, 2);
+
+        for value in 0..7 {
+            assert!(states.resolve::<StateD>(CollidingParam(value)).is_ok());
+        }
+        assert!(states.resolve::<StateD>(CollidingParam(100)).is_err());
+
+        let statistics = states.statistics();
+        assert_eq!(statistics.capacity, 7);
+        assert_eq!(statistics.live_states, 7);
+    }
+
+    #[test]
+    fn test_evict() {
+        let mut states = StateRegistry::with_limits(StateLimits {
+            initial_capacity: 2,
+            soft_limit: 3,
+            hard_limit: 4,
+        });
+
+        assert!(states.resolve::<StateE>(0).is_ok());
+        assert!(states.resolve::<StateE>(1).is_ok());
+        states.invalidate_states();
+        thread::sleep(Duration::from_millis(2));
+
+        assert!(states.resolve::<StateE>(2).is_ok());
+        assert!(states.resolve::<StateE>(3).is_ok());
+        states.invalidate_states();
+
+        /* soft limit exceeded, the least recently used state has been evicted */
+        assert_eq!(states.statistics().live_states, 3);
+        assert!(states.get::<StateE>(0).is_none());
+        assert!(states.get::<StateE>(3).is_some());
+
+        /* hard limit reached, states accessed within this frame will not be evicted */
+        assert!(states.resolve::<StateE>(4).is_ok());
+        assert!(states.resolve::<StateE>(5).is_ok());
+        assert!(states.get::<StateE>(1).is_none());
+        assert!(states.get::<StateE>(2).is_some());
+        assert!(states.resolve::<StateE>(6).is_ok());
+        assert!(states.resolve::<StateE>(7).is_ok());
+        assert!(states.get::<StateE>(2).is_none());
+        assert!(states.get::<StateE>(3).is_none());
+        assert!(states.resolve::<StateE>(8).is_err());
+
+        let statistics = states.statistics();
+        assert_eq!(statistics.live_states, 4);
+        let occupancy = statistics
+            .types
+            .iter()
+            .find(|(state_type, _)| state_type.ends_with("StateE"))
+            .map(|(_, occupancy)| occupancy)
+            .unwrap();
+        assert_eq!(occupancy.instances, 4);
+        assert_eq!(occupancy.evictions, 4);
+    }
+
+    struct StateMap(u64);
+    impl State for StateMap {
+        type Parameter = ();
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+
+        fn tags() -> &'static [&'static str] {
+            &["map"]
+        }
+    }
+
+    struct StateMapDerived(u64);
+    impl State for StateMapDerived {
+        type Parameter = u64;
+
+        fn create(states: &StateRegistry, params: Self::Parameter) -> anyhow::Result<Self> {
+            let map = states.resolve::<StateMap>(())?;
+            Ok(Self(map.0 + params))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+    }
+
+    struct StateMapDerivedIndirect(u64);
+    impl State for StateMapDerivedIndirect {
+        type Parameter = ();
+
+        fn create(states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(states.resolve::<StateMapDerived>(10)?.0))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+    }
+
+    #[test]
+    fn test_invalidate_tag() {
+        let mut states = StateRegistry::new(8);
+        states.set(StateMap(100), ()).unwrap();
+        assert_eq!(
+            states.resolve::<StateMapDerivedIndirect>(()).unwrap().0,
+            110
+        );
+        assert_eq!(states.resolve::<StateMapDerived>(20).unwrap().0, 120);
+        assert!(states.resolve::<StateB>(()).is_ok());
+        states.invalidate_states();
+
+        /* the map and all states derived from it are removed */
+        assert_eq!(states.invalidate_tag("map"), 4);
+        assert!(states.get::<StateMapDerivedIndirect>(()).is_none());
+        assert!(states.get::<StateB>(()).is_some());
+
+        states.set(StateMap(200), ()).unwrap();
+        assert_eq!(
+            states.resolve::<StateMapDerivedIndirect>(()).unwrap().0,
+            210
+        );
+    }
+
+    #[test]
+    fn test_invalidate_where() {
+        let mut states = StateRegistry::new(8);
+        states.set(StateMap(100), ()).unwrap();
+        assert!(states.resolve::<StateMapDerivedIndirect>(()).is_ok());
+        assert!(states.resolve::<StateMapDerived>(20).is_ok());
+
+        assert_eq!(
+            states.invalidate_where::<StateMapDerived>(|params, _value| *params == 10),
+            2
+        );
+        assert!(states.get::<StateMapDerivedIndirect>(()).is_none());
+        assert!(states.get::<StateMapDerived>(20).is_some());
+        assert!(states.get::<StateMap>(()).is_some());
+
+        assert_eq!(states.invalidate::<StateMapDerived>(20), 1);
+        assert_eq!(states.invalidate::<StateMapDerived>(20), 0);
+        assert_eq!(states.statistics().live_states, 1);
+    }
+
+    struct StateRefreshSource {
+        value: u64,
+        fail: bool,
+    }
+    impl State for StateRefreshSource {
+        type Parameter = ();
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+    }
+
+    /// Copies the source value and fails if the source is failing
+    struct StateRefresh(u64);
+    impl State for StateRefresh {
+        type Parameter = u64;
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(0))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Refresh(Duration::ZERO)
+        }
+
+        fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+            self.0 = states.resolve::<StateRefreshSource>(())?.value;
+            Ok(())
+        }
+
+        fn refresh(&self, states: &StateRegistry) -> Option<anyhow::Result<Self>> {
+            Some((|| {
+                let source = states.resolve::<StateRefreshSource>(())?;
+                anyhow::ensure!(!source.fail, "update failed");
+                Ok(Self(source.value))
+            })())
+        }
+    }
+
+    static REFRESH_CREATE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    /// Counts its creations and updates, refreshes in place
+    struct StateRefreshCounted(u64);
+    impl State for StateRefreshCounted {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            REFRESH_CREATE_COUNT.fetch_add(1, Ordering::Relaxed);
+            Ok(Self(0))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Refresh(Duration::ZERO)
+        }
+
+        fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    /// Refresh state which can only be set manually
+    struct StateRefreshManual(u64);
+    impl State for StateRefreshManual {
+        type Parameter = ();
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Refresh(Duration::ZERO)
+        }
+
+        fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    struct StateRefreshSlow(u64);
+    impl State for StateRefreshSlow {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(0))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Refresh(Duration::from_secs(3600))
+        }
+
+        fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_refresh() {
+        let mut states = StateRegistry::new(4);
+        for _ in 0..3 {
+            assert_eq!(states.resolve::<StateRefreshSlow>(()).unwrap().0, 1);
+            states.invalidate_states();
+        }
+
+        states
+            .set(
+                StateRefreshSource {
+                    value: 1,
+                    fail: false,
+                },
+                (),
+            )
+            .unwrap();
+        assert_eq!(states.resolve::<StateRefresh>(0).unwrap().0, 1);
+        states.get_mut::<StateRefreshSource>(()).unwrap().value = 2;
+        states.invalidate_states();
+        assert_eq!(states.resolve::<StateRefresh>(0).unwrap().0, 2);
+        assert!(!states.freshness::<StateRefresh>(0).unwrap().is_stale());
+
+        {
+            let mut source = states.get_mut::<StateRefreshSource>(()).unwrap();
+            source.value = 3;
+            source.fail = true;
+        }
+        states.invalidate_states();
+
+        /* the refresh fails, the last good value is being served */
+        assert_eq!(states.resolve::<StateRefresh>(0).unwrap().0, 2);
+        let freshness = states.freshness::<StateRefresh>(0).unwrap();
+        assert!(freshness.is_stale());
+        assert_eq!(freshness.last_error.as_deref(), Some("update failed"));
+        assert!(freshness.age().is_some());
+        assert!(states.freshness::<StateRefresh>(1).is_none());
+    }
+
+    #[test]
+    fn test_refresh_in_place() {
+        let mut states = StateRegistry::new(4);
+        for update_count in 1..=3 {
+            assert_eq!(
+                states.resolve::<StateRefreshCounted>(()).unwrap().0,
+                update_count
+            );
+            states.invalidate_states();
+        }
+
+        /* refreshing must not create the state again */
+        assert_eq!(REFRESH_CREATE_COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_refresh_manual() {
+        let mut states = StateRegistry::new(4);
+        states.set(StateRefreshManual(0), ()).unwrap();
+        assert_eq!(states.resolve::<StateRefreshManual>(()).unwrap().0, 0);
+
+        for update_count in 1..=2 {
+            states.invalidate_states();
+            assert_eq!(
+                states.resolve::<StateRefreshManual>(()).unwrap().0,
+                update_count
+            );
+            assert!(!states
+                .freshness::<StateRefreshManual>(())
+                .unwrap()
+                .is_stale());
+        }
+    }
+
     #[test]
     fn test_expire() {
         let mut states = StateRegistry::new(2);
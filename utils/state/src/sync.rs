@@ -0,0 +1,209 @@
+use std::{
+    any::{
+        Any,
+        TypeId,
+    },
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+        RwLock,
+    },
+    time::Instant,
+};
+
+use crate::{
+    params_equal,
+    state_key,
+    State,
+    StateRegistry,
+};
+
+/// Parameters of a state instance and the state value
+type SnapshotEntry = (Box<dyn Any + Send + Sync>, Box<dyn Any + Send + Sync>);
+
+/// A consistent set of state values captured during one update of a [SyncStateRegistry].
+pub struct StateSnapshot {
+    /// Snapshot entries by the cache key
+    values: HashMap<(TypeId, u64), Vec<SnapshotEntry>>,
+    created: Instant,
+}
+
+impl StateSnapshot {
+    fn new() -> Self {
+        Self {
+            values: Default::default(),
+            created: Instant::now(),
+        }
+    }
+
+    /// Time when the snapshot has been created
+    pub fn created(&self) -> Instant {
+        self.created
+    }
+
+    pub fn insert<T: State + Sync>(&mut self, value: T, params: T::Parameter)
+    where
+        T::Parameter: Sync,
+    {
+        let entries = self.values.entry(state_key::<T>(&params)).or_default();
+        match entries
+            .iter_mut()
+            .find(|(entry_params, _)| params_equal::<T>(entry_params.as_ref(), &params))
+        {
+            Some((_, entry_value)) => *entry_value = Box::new(value),
+            None => entries.push((Box::new(params), Box::new(value))),
+        }
+    }
+
+    /// Resolve the state and store a copy of it within the snapshot
+    pub fn capture<T: State + Clone + Sync>(
+        &mut self,
+        states: &StateRegistry,
+        params: T::Parameter,
+    ) -> anyhow::Result<()>
+    where
+        T::Parameter: Sync,
+    {
+        let value = states.resolve::<T>(params.clone())?.clone();
+        self.insert(value, params);
+        Ok(())
+    }
+
+    pub fn get<T: State + Sync>(&self, params: T::Parameter) -> Option<&T> {
+        self.values
+            .get(&state_key::<T>(&params))?
+            .iter()
+            .find(|(entry_params, _)| params_equal::<T>(entry_params.as_ref(), &params))
+            .map(|(_, value)| value.downcast_ref::<T>().expect("to be of type T"))
+    }
+}
+
+/// A [StateRegistry] which can be shared between threads.
+///
+/// States are resolved by one thread at a time (usually a worker thread) using [SyncStateRegistry::update]
+/// with the same caching and circular dependency semantics as the [StateRegistry].
+/// All values captured during an update are published at once, so readers always
+/// see a consistent snapshot without waiting for the next update to finish.
+pub struct SyncStateRegistry {
+    registry: Mutex<StateRegistry>,
+    snapshot: RwLock<Arc<StateSnapshot>>,
+}
+
+impl SyncStateRegistry {
+    pub fn new(registry: StateRegistry) -> Self {
+        Self {
+            registry: Mutex::new(registry),
+            snapshot: RwLock::new(Arc::new(StateSnapshot::new())),
+        }
+    }
+
+    /// Exclusive access to the underlying registry (e.g. to preset states).
+    pub fn lock(&self) -> MutexGuard<'_, StateRegistry> {
+        /* the registry stays consistent even if a state panicked */
+        self.registry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Invalidate the states and run the update callback.
+    /// Values captured by the callback will be published as the new snapshot
+    /// if the callback succeeds. On failure the previous snapshot is kept.
+    pub fn update(
+        &self,
+        callback: impl FnOnce(&StateRegistry, &mut StateSnapshot) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let mut registry = self.lock();
+        registry.invalidate_states();
+
+        let mut snapshot = StateSnapshot::new();
+        callback(&registry, &mut snapshot)?;
+
+        *self
+            .snapshot
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(snapshot);
+        Ok(())
+    }
+
+    /// The most recently published snapshot
+    pub fn snapshot(&self) -> Arc<StateSnapshot> {
+        self.snapshot
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::Arc,
+        thread,
+    };
+
+    use super::SyncStateRegistry;
+    use crate::{
+        State,
+        StateCacheType,
+        StateRegistry,
+    };
+
+    #[derive(Clone)]
+    struct StateCounter(u64);
+    impl State for StateCounter {
+        type Parameter = u64;
+
+        fn create(_states: &StateRegistry, params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(params))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+
+        fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_snapshot_from_worker() {
+        let registry = Arc::new(SyncStateRegistry::new(StateRegistry::new(4)));
+        assert!(registry.snapshot().get::<StateCounter>(10).is_none());
+
+        let worker = thread::spawn({
+            let registry = registry.clone();
+            move || {
+                for _ in 0..3 {
+                    registry
+                        .update(|states, snapshot| {
+                            snapshot.capture::<StateCounter>(states, 10)?;
+                            snapshot.capture::<StateCounter>(states, 20)?;
+                            Ok(())
+                        })
+                        .unwrap();
+                }
+            }
+        });
+        worker.join().unwrap();
+
+        let snapshot = registry.snapshot();
+        assert_eq!(
+            snapshot.get::<StateCounter>(10).map(|value| value.0),
+            Some(13)
+        );
+        assert_eq!(
+            snapshot.get::<StateCounter>(20).map(|value| value.0),
+            Some(23)
+        );
+
+        /* failed updates keep the previous snapshot */
+        assert!(registry
+            .update(|_states, _snapshot| anyhow::bail!("failure"))
+            .is_err());
+        assert!(Arc::ptr_eq(&snapshot, &registry.snapshot()));
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fmt::Write,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use serde::{
+    Serialize,
+    Serializer,
+};
+
+fn serialize_duration_us<S: Serializer>(
+    value: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(value.as_micros() as u64)
+}
+
+/// Resolve statistics of a single state instance within one frame
+#[derive(Debug, Clone, Serialize)]
+pub struct StateTraceNode {
+    /// Unique id of the state instance within the trace
+    pub id: usize,
+    pub state_type: &'static str,
+
+    /// Resolves served from the cache without creating or updating the state
+    pub cache_hits: usize,
+    /// Resolves which created the state
+    pub cache_misses: usize,
+    /// Resolves which updated the state
+    pub updates: usize,
+    pub errors: usize,
+
+    /// Time spent creating the state (including the time to resolve its dependencies)
+    #[serde(rename = "create_time_us", serialize_with = "serialize_duration_us")]
+    pub create_time: Duration,
+
+    /// Time spent updating the state (including the time to resolve its dependencies)
+    #[serde(rename = "update_time_us", serialize_with = "serialize_duration_us")]
+    pub update_time: Duration,
+}
+
+impl StateTraceNode {
+    pub fn total_time(&self) -> Duration {
+        self.create_time + self.update_time
+    }
+}
+
+/// States resolved during one frame (between two calls of `StateRegistry::invalidate_states`)
+/// including their dependencies on each other.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResolveTrace {
+    #[serde(rename = "duration_us", serialize_with = "serialize_duration_us")]
+    pub duration: Duration,
+
+    pub nodes: Vec<StateTraceNode>,
+
+    /// Dependencies as (dependent state id, dependency state id)
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl ResolveTrace {
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Render the dependency graph in the Graphviz DOT format
+    pub fn to_dot(&self) -> String {
+        let mut result = String::new();
+        let _ = writeln!(&mut result, "digraph states {{");
+        let _ = writeln!(&mut result, "    node [shape=box];");
+        for node in self.nodes.iter() {
+            let _ = writeln!(
+                &mut result,
+                "    s{} [label=\"{}\\nhits: {}, misses: {}, updates: {}\\ncreate: {:?}, update: {:?}\"{}];",
+                node.id,
+                node.state_type,
+                node.cache_hits,
+                node.cache_misses,
+                node.updates,
+                node.create_time,
+                node.update_time,
+                if node.errors > 0 { ", color=red" } else { "" }
+            );
+        }
+
+        for (dependent, dependency) in self.edges.iter() {
+            let _ = writeln!(&mut result, "    s{} -> s{};", dependent, dependency);
+        }
+        let _ = writeln!(&mut result, "}}");
+        result
+    }
+
+    /// Nodes ordered by their total create and update time, most expensive first
+    pub fn nodes_by_time(&self) -> Vec<&StateTraceNode> {
+        let mut nodes = self.nodes.iter().collect::<Vec<_>>();
+        nodes.sort_by_key(|node| std::cmp::Reverse(node.total_time()));
+        nodes
+    }
+}
+
+/// Records the resolve trace for the current frame.
+/// State instances are identified by their slot index which is stable within a frame.
+pub(crate) struct TraceRecorder {
+    started: Instant,
+    nodes: BTreeMap<usize, StateTraceNode>,
+    edges: BTreeSet<(usize, usize)>,
+    stack: Vec<usize>,
+}
+
+impl TraceRecorder {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            nodes: Default::default(),
+            edges: Default::default(),
+            stack: Vec::with_capacity(16),
+        }
+    }
+
+    pub fn enter(&mut self, index: usize, state_type: &'static str) {
+        if let Some(dependent) = self.stack.last() {
+            self.edges.insert((*dependent, index));
+        }
+        self.stack.push(index);
+
+        self.nodes.entry(index).or_insert_with(|| StateTraceNode {
+            id: index,
+            state_type,
+
+            cache_hits: 0,
+            cache_misses: 0,
+            updates: 0,
+            errors: 0,
+
+            create_time: Duration::ZERO,
+            update_time: Duration::ZERO,
+        });
+    }
+
+    pub fn leave(&mut self) {
+        self.stack.pop();
+    }
+
+    pub fn node_mut(&mut self, index: usize) -> Option<&mut StateTraceNode> {
+        self.nodes.get_mut(&index)
+    }
+
+    pub fn finish(self) -> ResolveTrace {
+        ResolveTrace {
+            duration: self.started.elapsed(),
+            nodes: self.nodes.into_values().collect(),
+            edges: self.edges.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        State,
+        StateCacheType,
+        StateRegistry,
+    };
+
+    struct StateLeaf;
+    impl State for StateLeaf {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+    }
+
+    struct StateRoot;
+    impl State for StateRoot {
+        type Parameter = u64;
+
+        fn create(states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            states.resolve::<StateLeaf>(())?;
+            Ok(Self)
+        }
+
+        fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+            states.resolve::<StateLeaf>(())?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_resolve_trace() {
+        let mut states = StateRegistry::new(8);
+        states.set_tracing(true);
+
+        states.resolve::<StateRoot>(1).unwrap();
+        states.resolve::<StateRoot>(2).unwrap();
+        states.resolve::<StateRoot>(1).unwrap();
+        states.invalidate_states();
+
+        let trace = states.last_trace().unwrap();
+        assert_eq!(trace.nodes.len(), 3);
+
+        let leaf = trace
+            .nodes
+            .iter()
+            .find(|node| node.state_type.ends_with("StateLeaf"))
+            .unwrap();
+        assert_eq!(leaf.cache_misses, 1);
+        assert_eq!(leaf.updates, 1);
+        assert_eq!(leaf.cache_hits, 3);
+
+        let roots = trace
+            .nodes
+            .iter()
+            .filter(|node| node.state_type.ends_with("StateRoot"))
+            .collect::<Vec<_>>();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots.iter().map(|node| node.cache_hits).sum::<usize>(), 1);
+        assert!(roots
+            .iter()
+            .all(|node| trace.edges.contains(&(node.id, leaf.id))));
+
+        assert!(trace
+            .to_dot()
+            .contains(&format!("s{} -> s{};", roots[0].id, leaf.id)));
+        assert!(trace.to_json().unwrap().contains("\"cache_hits\": 3"));
+
+        /* volatile roots have been removed, the persistent leaf stays alive */
+        let instances = states.instance_counts();
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances.values().sum::<usize>(), 1);
+    }
+}